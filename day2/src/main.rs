@@ -26,10 +26,10 @@ fn extract_data(lines: Vec<String>) -> Vec<Vec<i32>> {
     let mut reports = vec![];
 
     for line in lines {
-        let mut numbers = line.split_ascii_whitespace();
+        let numbers = line.split_ascii_whitespace();
 
         let mut levels = vec![];
-        while let Some(level) = numbers.next() {
+        for level in numbers {
             levels.push(level.parse::<i32>().unwrap());
         }
         reports.push(levels);
@@ -19,7 +19,7 @@ pub fn check_report(report: Vec<i32>) -> bool {
 
         let difference = (previous_level - level).abs();
 
-        if difference < 1 || 3 < difference {
+        if !(1..=3).contains(&difference) {
             return false;
         }
 
@@ -0,0 +1,287 @@
+use std::fmt;
+
+use crate::{
+    executor::{ExecutionError, Executor},
+    instruction_set::Semantics,
+    program::{Node, Parser},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Reason {
+    Unexpected { expected: &'static str, found: char },
+    OperandTooWide { width: usize, max_width: usize },
+    OperandOutOfRange,
+    EndOfInput,
+}
+
+/// A registered instruction name that is not followed by a valid instruction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NearMiss {
+    pub offset: usize,
+    pub fragment: String,
+    pub reason: Reason,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Report {
+    pub near_misses: Vec<NearMiss>,
+    /// Valid instructions that were skipped because execution was disabled.
+    pub suppressed: Vec<Node>,
+    /// Why execution stopped before the end, leaving `suppressed` short.
+    pub stopped: Option<ExecutionError>,
+}
+
+impl Parser {
+    pub fn diagnose(&self, source: &str) -> Report {
+        let program = self.parse(source);
+        let mut near_misses = vec![];
+
+        let mut names: Vec<&str> = self
+            .instruction_set
+            .operations()
+            .map(|operation| operation.name.as_str())
+            .collect();
+        names.sort_by_key(|name| std::cmp::Reverse(name.len()));
+
+        let mut nodes = program.nodes.iter().peekable();
+        for offset in 0..source.len() {
+            while nodes.next_if(|node| node.span.end <= offset).is_some() {}
+            if nodes.peek().is_some_and(|node| node.span.contains(&offset)) {
+                continue;
+            }
+
+            let Some(name) = names
+                .iter()
+                .find(|name| source.as_bytes()[offset..].starts_with(name.as_bytes()))
+            else {
+                continue;
+            };
+            let arity = self.instruction_set.get(name).unwrap().arity;
+
+            let (end, reason) = self.find_defect(source, offset + name.len(), arity);
+            let end = end.min(source.len());
+            near_misses.push(NearMiss {
+                offset,
+                fragment: String::from_utf8_lossy(&source.as_bytes()[offset..end]).into_owned(),
+                reason,
+            });
+        }
+
+        let mut executor = Executor::with_instruction_set(self.instruction_set.clone());
+        let mut suppressed = vec![];
+        let mut stopped = None;
+        for node in &program.nodes {
+            let step = match executor.step(node) {
+                Ok(step) => step,
+                Err(error) => {
+                    stopped = Some(error);
+                    break;
+                }
+            };
+            let arithmetic = matches!(
+                self.instruction_set.get(&step.instruction.name),
+                Some(operation) if matches!(operation.semantics, Semantics::Arithmetic(_))
+            );
+            if !step.executed && arithmetic {
+                suppressed.push(node.clone());
+            }
+        }
+
+        Report {
+            near_misses,
+            suppressed,
+            stopped,
+        }
+    }
+
+    /// Walks the expected `(a,b,..)` after an instruction name and returns
+    /// where and why it stops being valid.
+    fn find_defect(&self, source: &str, start: usize, arity: usize) -> (usize, Reason) {
+        let bytes = source.as_bytes();
+        let mut position = start;
+
+        let expect = |position: &mut usize, expected: &'static str, accepted: u8| {
+            if bytes.get(*position) == Some(&accepted) {
+                *position += 1;
+                None
+            } else {
+                Some(unexpected(source, *position, expected))
+            }
+        };
+
+        if let Some(reason) = expect(&mut position, "'('", b'(') {
+            return (position + 1, reason);
+        }
+
+        for index in 0..arity {
+            let digits = bytes[position..]
+                .iter()
+                .take_while(|byte| byte.is_ascii_digit())
+                .count();
+            if digits == 0 {
+                return (position + 1, unexpected(source, position, "an operand"));
+            }
+            position += digits;
+            if digits > self.max_operand_width {
+                return (
+                    position,
+                    Reason::OperandTooWide {
+                        width: digits,
+                        max_width: self.max_operand_width,
+                    },
+                );
+            }
+
+            if index + 1 < arity {
+                if let Some(reason) = expect(&mut position, "','", b',') {
+                    return (position + 1, reason);
+                }
+            }
+        }
+
+        if let Some(reason) = expect(&mut position, "')'", b')') {
+            return (position + 1, reason);
+        }
+
+        (position, Reason::OperandOutOfRange)
+    }
+}
+
+fn unexpected(source: &str, position: usize, expected: &'static str) -> Reason {
+    // Offsets come from byte scanning and may land inside a multi-byte char.
+    match String::from_utf8_lossy(&source.as_bytes()[position..])
+        .chars()
+        .next()
+    {
+        Some(found) => Reason::Unexpected { expected, found },
+        None => Reason::EndOfInput,
+    }
+}
+
+impl fmt::Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Reason::Unexpected { expected, found } => {
+                write!(f, "expected {expected}, found {found:?}")
+            }
+            Reason::OperandTooWide { width, max_width } => {
+                write!(f, "operand has {width} digits, at most {max_width} allowed")
+            }
+            Reason::OperandOutOfRange => write!(f, "operand out of range"),
+            Reason::EndOfInput => write!(f, "unexpected end of input"),
+        }
+    }
+}
+
+impl fmt::Display for NearMiss {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {:?} {}", self.offset, self.fragment, self.reason)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        diagnostics::{NearMiss, Reason},
+        executor::ExecutionError,
+        program::{Instruction, Parser},
+    };
+
+    #[test]
+    fn should_report_near_misses_with_offset_and_reason() {
+        let report = Parser::new().diagnose("mul(13d34)x mul ( 2,3)mul(2,3]mul(1234,5)");
+
+        assert_eq!(
+            report.near_misses,
+            vec![
+                NearMiss {
+                    offset: 0,
+                    fragment: String::from("mul(13d"),
+                    reason: Reason::Unexpected {
+                        expected: "','",
+                        found: 'd'
+                    },
+                },
+                NearMiss {
+                    offset: 12,
+                    fragment: String::from("mul "),
+                    reason: Reason::Unexpected {
+                        expected: "'('",
+                        found: ' '
+                    },
+                },
+                NearMiss {
+                    offset: 22,
+                    fragment: String::from("mul(2,3]"),
+                    reason: Reason::Unexpected {
+                        expected: "')'",
+                        found: ']'
+                    },
+                },
+                NearMiss {
+                    offset: 30,
+                    fragment: String::from("mul(1234"),
+                    reason: Reason::OperandTooWide {
+                        width: 4,
+                        max_width: 3
+                    },
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn should_not_report_valid_instructions() {
+        let report = Parser::new().diagnose("mul(2,3)do()don't()");
+
+        assert!(report.near_misses.is_empty());
+    }
+
+    #[test]
+    fn should_report_multiplications_suppressed_by_dont() {
+        let report = Parser::new().diagnose("mul(1,2)don't()mul(3,4)do()mul(5,6)");
+
+        assert_eq!(
+            report
+                .suppressed
+                .into_iter()
+                .map(|node| (node.instruction, node.span))
+                .collect::<Vec<_>>(),
+            vec![(Instruction::new("mul", vec![3, 4]), 15..23)]
+        );
+    }
+
+    #[test]
+    fn should_report_truncated_instruction_at_end_of_input() {
+        let report = Parser::new().diagnose("mul(2,");
+
+        assert_eq!(
+            report.near_misses,
+            vec![NearMiss {
+                offset: 0,
+                fragment: String::from("mul(2,"),
+                reason: Reason::EndOfInput,
+            }]
+        );
+    }
+
+    #[test]
+    fn should_record_where_execution_stopped() {
+        let report = Parser::new()
+            .max_operand_width(10)
+            .diagnose("don't()mul(1,2)do()mul(4000000000,4000000000)don't()mul(3,4)");
+
+        assert_eq!(
+            report
+                .suppressed
+                .into_iter()
+                .map(|node| node.span)
+                .collect::<Vec<_>>(),
+            vec![7..15]
+        );
+        assert_eq!(
+            report.stopped,
+            Some(ExecutionError::Overflow { span: 19..45 })
+        );
+    }
+}
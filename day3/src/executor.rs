@@ -0,0 +1,241 @@
+use std::{error::Error, fmt, ops::Range};
+
+use crate::{
+    instruction_set::{InstructionSet, Semantics},
+    program::{Instruction, Node, Program},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum State {
+    Enabled,
+    Disabled,
+}
+
+/// What happened when a single node was executed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Step {
+    pub instruction: Instruction,
+    pub span: Range<usize>,
+    pub state: State,
+    pub executed: bool,
+    pub sum: i64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExecutionError {
+    /// The instruction at `span` made the sum leave the `i64` range.
+    Overflow { span: Range<usize> },
+}
+
+#[derive(Debug)]
+pub struct Executor {
+    instruction_set: InstructionSet,
+    state: State,
+    sum: i64,
+    conditional: bool,
+}
+
+impl Default for Executor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Executor {
+    pub fn new() -> Executor {
+        Executor::with_instruction_set(InstructionSet::standard())
+    }
+
+    pub fn with_instruction_set(instruction_set: InstructionSet) -> Executor {
+        Executor {
+            instruction_set,
+            state: State::Enabled,
+            sum: 0,
+            conditional: true,
+        }
+    }
+
+    /// Executor that ignores control instructions such as `don't()`, as in
+    /// part one.
+    pub fn unconditional() -> Executor {
+        Executor::new().conditional(false)
+    }
+
+    /// Whether control instructions enable and disable the arithmetic ones.
+    /// Executors are conditional unless told otherwise.
+    pub fn conditional(mut self, conditional: bool) -> Executor {
+        self.conditional = conditional;
+        self
+    }
+
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    pub fn sum(&self) -> i64 {
+        self.sum
+    }
+
+    pub fn step(&mut self, node: &Node) -> Result<Step, ExecutionError> {
+        let mut executed = false;
+        let operation = self
+            .instruction_set
+            .get(&node.instruction.name)
+            .filter(|operation| operation.arity == node.instruction.operands.len());
+
+        match operation.map(|operation| operation.semantics) {
+            Some(Semantics::Control(state)) if self.conditional => self.state = state,
+            Some(Semantics::Arithmetic(compute)) if self.state == State::Enabled => {
+                self.sum = compute(&node.instruction.operands)
+                    .and_then(|value| self.sum.checked_add(value))
+                    .ok_or(ExecutionError::Overflow {
+                        span: node.span.clone(),
+                    })?;
+                executed = true;
+            }
+            _ => {}
+        }
+
+        Ok(Step {
+            instruction: node.instruction.clone(),
+            span: node.span.clone(),
+            state: self.state,
+            executed,
+            sum: self.sum,
+        })
+    }
+
+    pub fn run(&mut self, program: &Program) -> Result<i64, ExecutionError> {
+        for node in &program.nodes {
+            self.step(node)?;
+        }
+        Ok(self.sum)
+    }
+
+    pub fn trace(&mut self, program: &Program) -> Result<Vec<Step>, ExecutionError> {
+        program.nodes.iter().map(|node| self.step(node)).collect()
+    }
+}
+
+impl fmt::Display for ExecutionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExecutionError::Overflow { span } => {
+                write!(f, "sum overflows at bytes {}..{}", span.start, span.end)
+            }
+        }
+    }
+}
+
+impl Error for ExecutionError {}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        executor::{ExecutionError, Executor, State},
+        instruction_set::{InstructionSet, Semantics},
+        program::{Instruction, Parser, Program},
+    };
+
+    #[test]
+    fn should_run_program_with_conditions() {
+        let program = Program::parse(
+            "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))",
+        );
+
+        assert_eq!(Executor::new().run(&program), Ok(48));
+    }
+
+    #[test]
+    fn should_ignore_conditions_when_unconditional() {
+        let program = Program::parse(
+            "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))",
+        );
+
+        assert_eq!(Executor::unconditional().run(&program), Ok(161));
+    }
+
+    #[test]
+    fn should_trace_every_step() {
+        let program = Program::parse("mul(2,3)don't()mul(4,5)do()");
+
+        let trace = Executor::new().trace(&program).unwrap();
+
+        assert_eq!(
+            trace
+                .iter()
+                .map(|step| (
+                    step.instruction.clone(),
+                    step.state,
+                    step.executed,
+                    step.sum
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (Instruction::new("mul", vec![2, 3]), State::Enabled, true, 6),
+                (Instruction::new("don't", vec![]), State::Disabled, false, 6),
+                (
+                    Instruction::new("mul", vec![4, 5]),
+                    State::Disabled,
+                    false,
+                    6
+                ),
+                (Instruction::new("do", vec![]), State::Enabled, false, 6),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_execute_registered_operations() {
+        let instruction_set = InstructionSet::standard()
+            .register("sub", 2, Semantics::Arithmetic(|o| o[0].checked_sub(o[1])))
+            .register("halt", 0, Semantics::Control(State::Disabled))
+            .register("resume", 0, Semantics::Control(State::Enabled));
+        let program = Parser::with_instruction_set(instruction_set.clone())
+            .parse("sub(10,3)halt()mul(2,2)resume()mul(3,3)don't()sub(1,1)");
+
+        assert_eq!(
+            Executor::with_instruction_set(instruction_set).run(&program),
+            Ok(16)
+        );
+    }
+
+    #[test]
+    fn should_execute_registered_operations_unconditionally() {
+        let instruction_set = InstructionSet::standard()
+            .register("sub", 2, Semantics::Arithmetic(|o| o[0].checked_sub(o[1])))
+            .register("halt", 0, Semantics::Control(State::Disabled));
+        let program = Parser::with_instruction_set(instruction_set.clone())
+            .parse("sub(10,3)halt()mul(2,2)don't()sub(9,4)");
+
+        assert_eq!(
+            Executor::with_instruction_set(instruction_set)
+                .conditional(false)
+                .run(&program),
+            Ok(16)
+        );
+    }
+
+    #[test]
+    fn should_accumulate_beyond_i32() {
+        let program = Program::parse(&"mul(999,999)".repeat(3000));
+
+        assert_eq!(Executor::new().run(&program), Ok(2_994_003_000));
+    }
+
+    #[test]
+    fn should_report_overflow_as_error() {
+        let instruction_set = InstructionSet::standard().register(
+            "pow",
+            2,
+            Semantics::Arithmetic(|o| o[0].checked_pow(o[1] as u32)),
+        );
+        let program = Parser::with_instruction_set(instruction_set.clone())
+            .parse("mul(2,3)pow(2,62)pow(2,62)");
+
+        assert_eq!(
+            Executor::with_instruction_set(instruction_set).run(&program),
+            Err(ExecutionError::Overflow { span: 17..26 })
+        );
+    }
+}
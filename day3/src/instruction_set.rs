@@ -0,0 +1,95 @@
+use crate::executor::State;
+
+#[derive(Debug, Clone, Copy)]
+pub enum Semantics {
+    /// Computes a value from the operands and adds it to the running sum,
+    /// or `None` when it overflows.
+    Arithmetic(fn(&[i64]) -> Option<i64>),
+    /// Switches the executor into the given state.
+    Control(State),
+}
+
+#[derive(Debug, Clone)]
+pub struct Operation {
+    pub name: String,
+    pub arity: usize,
+    pub semantics: Semantics,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct InstructionSet {
+    operations: Vec<Operation>,
+}
+
+impl InstructionSet {
+    pub fn empty() -> InstructionSet {
+        InstructionSet::default()
+    }
+
+    /// `mul(a,b)`, `do()` and `don't()`, as found in the puzzle.
+    pub fn standard() -> InstructionSet {
+        InstructionSet::empty()
+            .register(
+                "mul",
+                2,
+                Semantics::Arithmetic(|operands| operands[0].checked_mul(operands[1])),
+            )
+            .register("do", 0, Semantics::Control(State::Enabled))
+            .register("don't", 0, Semantics::Control(State::Disabled))
+    }
+
+    /// Adds an operation, replacing any previous one with the same name.
+    pub fn register(mut self, name: &str, arity: usize, semantics: Semantics) -> InstructionSet {
+        self.operations.retain(|operation| operation.name != name);
+        self.operations.push(Operation {
+            name: String::from(name),
+            arity,
+            semantics,
+        });
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Operation> {
+        self.operations
+            .iter()
+            .find(|operation| operation.name == name)
+    }
+
+    pub fn operations(&self) -> impl Iterator<Item = &Operation> {
+        self.operations.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        executor::State,
+        instruction_set::{InstructionSet, Semantics},
+    };
+
+    #[test]
+    fn should_register_new_operations() {
+        let instruction_set = InstructionSet::standard().register(
+            "add",
+            2,
+            Semantics::Arithmetic(|o| o[0].checked_add(o[1])),
+        );
+
+        let add = instruction_set.get("add").unwrap();
+
+        assert_eq!(add.arity, 2);
+        assert_eq!(instruction_set.operations().count(), 4);
+    }
+
+    #[test]
+    fn should_replace_operation_with_same_name() {
+        let instruction_set =
+            InstructionSet::standard().register("do", 1, Semantics::Control(State::Disabled));
+
+        assert!(matches!(
+            instruction_set.get("do").unwrap().semantics,
+            Semantics::Control(State::Disabled)
+        ));
+        assert_eq!(instruction_set.operations().count(), 3);
+    }
+}
@@ -0,0 +1,6 @@
+pub mod diagnostics;
+pub mod executor;
+pub mod instruction_set;
+pub mod printer;
+pub mod program;
+pub mod stream;
@@ -1,13 +1,27 @@
-use std::fs::read_to_string;
+use std::{
+    env,
+    fs::{read_to_string, File},
+};
 
-use regex::Regex;
+use day3::{
+    executor::{ExecutionError, Executor},
+    program::{Node, Parser, Program},
+};
 
-#[derive(PartialEq)]
-enum Instruction {
-    Do,
-    Dont,
-}
 fn main() {
+    if env::args().any(|arg| arg == "--diagnose") {
+        diagnose(&read_to_string("day3/src/input").unwrap());
+        return;
+    }
+    if env::args().any(|arg| arg == "--print") {
+        print_cleaned(&read_to_string("day3/src/input").unwrap());
+        return;
+    }
+    if env::args().any(|arg| arg == "--stream") {
+        stream("day3/src/input");
+        return;
+    }
+
     let lines: Vec<String> = read_to_string("day3/src/input")
         .unwrap()
         .lines()
@@ -17,60 +31,82 @@ fn main() {
     let cleared_program = extract_data(lines.clone());
     let cleared_program_with_condition = extract_data_with_condition(lines);
 
-    println! {"{:?}", execute_cleared_program(cleared_program)};
-    println! {"{:?}", execute_cleared_program(cleared_program_with_condition)};
+    for sum in [
+        execute_cleared_program(&cleared_program),
+        cleared_program_with_condition.and_then(|program| execute_cleared_program(&program)),
+    ] {
+        match sum {
+            Ok(sum) => println! {"{:?}", sum},
+            Err(error) => eprintln!("day3/src/input: {error}"),
+        }
+    }
 }
 
-fn extract_data(lines: Vec<String>) -> Vec<(i32, i32)> {
-    let mul_regex = Regex::new(r"mul\((?<first>\d+),(?<second>\d+)\)").unwrap();
-    let mut multiplication_numbers = vec![];
+fn diagnose(source: &str) {
+    let report = Parser::new().diagnose(source);
 
-    for line in lines {
-        for caps in mul_regex.captures_iter(&line) {
-            let first_number: i32 = caps["first"].parse().unwrap();
-            let second_number: i32 = caps["second"].parse().unwrap();
-            multiplication_numbers.push((first_number, second_number));
-        }
+    for near_miss in report.near_misses {
+        println!("{near_miss}");
+    }
+    for node in report.suppressed {
+        println!(
+            "{}: {:?} suppressed while disabled",
+            node.span.start,
+            &source[node.span.clone()]
+        );
+    }
+    if let Some(error) = report.stopped {
+        println!("execution stopped: {error}");
     }
+}
+
+fn print_cleaned(source: &str) {
+    let program = Program::parse(source);
 
-    multiplication_numbers
+    print!("{}", program.render(&mut Executor::new()));
+    println!("{}", program.to_canonical());
 }
 
-fn extract_data_with_condition(lines: Vec<String>) -> Vec<(i32, i32)> {
-    let mul_regex = Regex::new(r"(do\(\)|don't\(\)|mul\((?<first>\d+),(?<second>\d+)\))").unwrap();
-    let mut multiplication_numbers = vec![];
-    let mut instruction = Instruction::Do;
-
-    for line in lines {
-        for caps in mul_regex.captures_iter(&line) {
-            match &caps[0] {
-                "do()" => instruction = Instruction::Do,
-                "don't()" => instruction = Instruction::Dont,
-                _ => {
-                    if instruction == Instruction::Do {
-                        let first_number: i32 = caps["first"].parse().unwrap();
-                        let second_number: i32 = caps["second"].parse().unwrap();
-                        multiplication_numbers.push((first_number, second_number));
-                    }
-                }
-            }
+fn stream(path: &str) {
+    let parser = Parser::new();
+
+    for mut executor in [Executor::unconditional(), Executor::new()] {
+        match executor.run_stream(parser.scan(File::open(path).unwrap())) {
+            Ok(sum) => println!("{sum}"),
+            Err(error) => eprintln!("{error}"),
         }
     }
+}
 
-    multiplication_numbers
+fn extract_data(lines: Vec<String>) -> Program {
+    Program::parse(&lines.join("\n"))
 }
 
-fn execute_cleared_program(numbers: Vec<(i32, i32)>) -> i32 {
-    let mut sum = 0;
-    for (first_num, second_num) in numbers {
-        sum += first_num * second_num;
-    }
-    sum
+/// Only the instructions executed while enabled.
+fn extract_data_with_condition(lines: Vec<String>) -> Result<Program, ExecutionError> {
+    let program = Program::parse(&lines.join("\n"));
+
+    let nodes = Executor::new()
+        .trace(&program)?
+        .into_iter()
+        .filter(|step| step.executed)
+        .map(|step| Node {
+            instruction: step.instruction,
+            span: step.span,
+        })
+        .collect();
+    Ok(Program { nodes })
+}
+
+fn execute_cleared_program(program: &Program) -> Result<i64, ExecutionError> {
+    Executor::unconditional().run(program)
 }
 
 #[cfg(test)]
 mod test {
-    use crate::{extract_data, extract_data_with_condition};
+    use day3::{executor::ExecutionError, program::Parser};
+
+    use crate::{execute_cleared_program, extract_data, extract_data_with_condition};
 
     #[test]
     fn should_extract_data() {
@@ -79,6 +115,7 @@ mod test {
             extract_data(vec![String::from(
                 "aaamul(17,938)_dkjfb,mul(13d34)sdfmul(3,2)"
             )])
+            .multiplications()
         );
     }
 
@@ -89,6 +126,20 @@ mod test {
             extract_data_with_condition(vec![String::from(
                 "aaamul(17,938)_dkjfbdon't(),mul(13d34)sdfmul(3,2)do()mul(1,2)"
             )])
+            .unwrap()
+            .multiplications()
+        );
+    }
+
+    #[test]
+    fn should_report_overflow_instead_of_panicking() {
+        let program = Parser::new()
+            .max_operand_width(10)
+            .parse("mul(1,2)mul(4000000000,4000000000)");
+
+        assert_eq!(
+            execute_cleared_program(&program),
+            Err(ExecutionError::Overflow { span: 8..34 })
         );
     }
 }
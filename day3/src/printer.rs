@@ -0,0 +1,89 @@
+use std::fmt;
+
+use crate::{
+    executor::{Executor, State},
+    program::{Instruction, Program},
+};
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let operands: Vec<String> = self.operands.iter().map(i64::to_string).collect();
+        write!(f, "{}({})", self.name, operands.join(","))
+    }
+}
+
+impl Program {
+    /// The instructions back to back with the noise removed. Parsing it
+    /// again gives the same instruction list.
+    pub fn to_canonical(&self) -> String {
+        self.nodes
+            .iter()
+            .map(|node| node.instruction.to_string())
+            .collect()
+    }
+
+    /// One instruction per line, prefixed with `+` while execution is
+    /// enabled and `-` while it is disabled.
+    pub fn render(&self, executor: &mut Executor) -> String {
+        let mut rendered = String::new();
+
+        for node in &self.nodes {
+            // An overflowing sum does not change the state, keep printing.
+            let state = executor
+                .step(node)
+                .map_or(executor.state(), |step| step.state);
+            let marker = match state {
+                State::Enabled => '+',
+                State::Disabled => '-',
+            };
+            rendered.push_str(&format!("{marker} {}\n", node.instruction));
+        }
+
+        rendered
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        executor::Executor,
+        program::{Node, Program},
+    };
+
+    const MEMORY: &str =
+        "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+
+    #[test]
+    fn should_render_enabled_and_disabled_regions() {
+        let program = Program::parse(MEMORY);
+
+        assert_eq!(
+            program.render(&mut Executor::new()),
+            "+ mul(2,4)\n- don't()\n- mul(5,5)\n- mul(11,8)\n+ do()\n+ mul(8,5)\n"
+        );
+    }
+
+    #[test]
+    fn should_emit_canonical_program() {
+        assert_eq!(
+            Program::parse(MEMORY).to_canonical(),
+            "mul(2,4)don't()mul(5,5)mul(11,8)do()mul(8,5)"
+        );
+    }
+
+    #[test]
+    fn should_parse_canonical_program_back_to_same_instructions() {
+        let program = Program::parse(MEMORY);
+
+        let reparsed = Program::parse(&program.to_canonical());
+
+        let instructions = |program: Program| {
+            program
+                .nodes
+                .into_iter()
+                .map(|node: Node| node.instruction)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(instructions(reparsed), instructions(program));
+    }
+}
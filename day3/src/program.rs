@@ -0,0 +1,231 @@
+use std::ops::Range;
+
+use regex::{escape, Regex};
+
+use crate::instruction_set::InstructionSet;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Instruction {
+    pub name: String,
+    pub operands: Vec<i64>,
+}
+
+/// An instruction together with the byte range it was read from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node {
+    pub instruction: Instruction,
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Program {
+    pub nodes: Vec<Node>,
+}
+
+/// The puzzle only allows `mul` operands of one to three digits.
+pub const DEFAULT_MAX_OPERAND_WIDTH: usize = 3;
+
+#[derive(Debug, Clone)]
+pub struct Parser {
+    pub(crate) instruction_set: InstructionSet,
+    pub(crate) max_operand_width: usize,
+}
+
+impl Default for Parser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Instruction {
+    pub fn new(name: &str, operands: Vec<i64>) -> Instruction {
+        Instruction {
+            name: String::from(name),
+            operands,
+        }
+    }
+}
+
+impl Parser {
+    pub fn new() -> Parser {
+        Parser::with_instruction_set(InstructionSet::standard())
+    }
+
+    pub fn with_instruction_set(instruction_set: InstructionSet) -> Parser {
+        Parser {
+            instruction_set,
+            max_operand_width: DEFAULT_MAX_OPERAND_WIDTH,
+        }
+    }
+
+    pub fn max_operand_width(mut self, max_operand_width: usize) -> Parser {
+        self.max_operand_width = max_operand_width.max(1);
+        self
+    }
+
+    pub fn instruction_set(&self) -> &InstructionSet {
+        &self.instruction_set
+    }
+
+    pub(crate) fn instruction_regex(&self) -> String {
+        let operand = format!(r"\d{{1,{}}}", self.max_operand_width);
+
+        let mut operations: Vec<_> = self.instruction_set.operations().collect();
+        // Longest names first, so `don't` is never cut short by a `do`.
+        operations.sort_by_key(|operation| std::cmp::Reverse(operation.name.len()));
+
+        operations
+            .iter()
+            .map(|operation| {
+                format!(
+                    r"{}\({}\)",
+                    escape(&operation.name),
+                    vec![operand.as_str(); operation.arity].join(",")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("|")
+    }
+
+    pub fn parse(&self, source: &str) -> Program {
+        let mut nodes = vec![];
+        if self.instruction_set.operations().next().is_none() {
+            return Program { nodes };
+        }
+        let instruction_regex = Regex::new(&self.instruction_regex()).unwrap();
+
+        for matched in instruction_regex.find_iter(source) {
+            if let Some(instruction) = parse_instruction(matched.as_str()) {
+                nodes.push(Node {
+                    instruction,
+                    span: matched.range(),
+                });
+            }
+        }
+
+        Program { nodes }
+    }
+}
+
+/// Splits an already matched `name(a,b,..)` into its parts. Operands that do
+/// not fit make the whole instruction as corrupt as any other noise.
+pub(crate) fn parse_instruction(text: &str) -> Option<Instruction> {
+    let (name, arguments) = text.strip_suffix(')')?.split_once('(')?;
+
+    let operands = if arguments.is_empty() {
+        vec![]
+    } else {
+        arguments
+            .split(',')
+            .map(|operand| operand.parse().ok())
+            .collect::<Option<Vec<_>>>()?
+    };
+
+    Some(Instruction::new(name, operands))
+}
+
+impl Program {
+    pub fn parse(source: &str) -> Program {
+        Parser::new().parse(source)
+    }
+
+    pub fn multiplications(&self) -> Vec<(i64, i64)> {
+        self.nodes
+            .iter()
+            .filter_map(|node| match node.instruction.operands[..] {
+                [first, second] if node.instruction.name == "mul" => Some((first, second)),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        instruction_set::{InstructionSet, Semantics},
+        program::{Instruction, Node, Parser, Program},
+    };
+
+    #[test]
+    fn should_parse_instructions_with_spans() {
+        let program = Program::parse("xmul(2,4)&don't()_mul(5,5)+do()?");
+
+        assert_eq!(
+            program.nodes,
+            vec![
+                Node {
+                    instruction: Instruction::new("mul", vec![2, 4]),
+                    span: 1..9
+                },
+                Node {
+                    instruction: Instruction::new("don't", vec![]),
+                    span: 10..17
+                },
+                Node {
+                    instruction: Instruction::new("mul", vec![5, 5]),
+                    span: 18..26
+                },
+                Node {
+                    instruction: Instruction::new("do", vec![]),
+                    span: 27..31
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn should_list_every_multiplication() {
+        let program = Program::parse("mul(1,2)don't()mul(3,4)");
+
+        assert_eq!(program.multiplications(), vec![(1, 2), (3, 4)]);
+    }
+
+    #[test]
+    fn should_skip_operands_wider_than_three_digits() {
+        let program = Program::parse("mul(1234,5)mul(123,4)mul(12,3456)");
+
+        assert_eq!(program.multiplications(), vec![(123, 4)]);
+    }
+
+    #[test]
+    fn should_accept_wider_operands_when_configured() {
+        let program = Parser::new()
+            .max_operand_width(4)
+            .parse("mul(1234,5)mul(12345,6)");
+
+        assert_eq!(program.multiplications(), vec![(1234, 5)]);
+    }
+
+    #[test]
+    fn should_skip_operands_overflowing_instead_of_panicking() {
+        let program = Parser::new()
+            .max_operand_width(20)
+            .parse("mul(99999999999999999999,2)mul(7,8)");
+
+        assert_eq!(program.multiplications(), vec![(7, 8)]);
+    }
+
+    #[test]
+    fn should_scan_registered_operations() {
+        let instruction_set = InstructionSet::standard()
+            .register("add", 2, Semantics::Arithmetic(|o| o[0].checked_add(o[1])))
+            .register("neg", 1, Semantics::Arithmetic(|o| o[0].checked_neg()));
+
+        let program = Parser::with_instruction_set(instruction_set)
+            .parse("add(1,2)sub(3,4)neg(5)neg(5,6)mul(7,8)");
+
+        assert_eq!(
+            program
+                .nodes
+                .into_iter()
+                .map(|node| node.instruction)
+                .collect::<Vec<_>>(),
+            vec![
+                Instruction::new("add", vec![1, 2]),
+                Instruction::new("neg", vec![5]),
+                Instruction::new("mul", vec![7, 8]),
+            ]
+        );
+    }
+}
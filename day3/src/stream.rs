@@ -0,0 +1,200 @@
+use std::{
+    collections::VecDeque,
+    error::Error,
+    fmt,
+    io::{self, Read},
+    str,
+};
+
+use regex::bytes::Regex;
+
+use crate::{
+    executor::{ExecutionError, Executor},
+    program::{parse_instruction, Node, Parser},
+};
+
+pub const DEFAULT_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Scans a reader chunk by chunk, holding at most one chunk plus the tail of
+/// a possibly unfinished instruction in memory.
+pub struct StreamScanner<R> {
+    reader: R,
+    regex: Option<Regex>,
+    chunk_size: usize,
+    max_instruction_len: usize,
+    buffer: Vec<u8>,
+    buffer_offset: usize,
+    pending: VecDeque<Node>,
+    end_of_input: bool,
+}
+
+#[derive(Debug)]
+pub enum StreamError {
+    Io(io::Error),
+    Execution(ExecutionError),
+}
+
+impl Parser {
+    pub fn scan<R: Read>(&self, reader: R) -> StreamScanner<R> {
+        let max_instruction_len = self
+            .instruction_set
+            .operations()
+            .map(|operation| {
+                operation.name.len()
+                    + 2
+                    + operation.arity * self.max_operand_width
+                    + operation.arity.saturating_sub(1)
+            })
+            .max()
+            .unwrap_or(0);
+
+        StreamScanner {
+            reader,
+            regex: (max_instruction_len > 0)
+                .then(|| Regex::new(&self.instruction_regex()).unwrap()),
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            max_instruction_len,
+            buffer: vec![],
+            buffer_offset: 0,
+            pending: VecDeque::new(),
+            end_of_input: false,
+        }
+    }
+}
+
+impl<R: Read> StreamScanner<R> {
+    pub fn chunk_size(mut self, chunk_size: usize) -> StreamScanner<R> {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    fn fill(&mut self) -> io::Result<()> {
+        let Some(regex) = &self.regex else {
+            self.end_of_input = true;
+            return Ok(());
+        };
+
+        let read = self
+            .reader
+            .by_ref()
+            .take(self.chunk_size as u64)
+            .read_to_end(&mut self.buffer)?;
+        self.end_of_input = read == 0;
+
+        // An instruction starting before `safe_end` lies entirely in the
+        // buffer; later ones may still be cut by the chunk boundary.
+        let safe_end = if self.end_of_input {
+            self.buffer.len()
+        } else {
+            self.buffer
+                .len()
+                .saturating_sub(self.max_instruction_len - 1)
+        };
+
+        let mut consumed = 0;
+        for matched in regex.find_iter(&self.buffer) {
+            if matched.start() >= safe_end {
+                break;
+            }
+            consumed = matched.end();
+
+            let instruction = str::from_utf8(matched.as_bytes())
+                .ok()
+                .and_then(parse_instruction);
+            if let Some(instruction) = instruction {
+                self.pending.push_back(Node {
+                    instruction,
+                    span: self.buffer_offset + matched.start()..self.buffer_offset + matched.end(),
+                });
+            }
+        }
+
+        let kept_from = consumed.max(safe_end);
+        self.buffer.drain(..kept_from);
+        self.buffer_offset += kept_from;
+        Ok(())
+    }
+}
+
+impl<R: Read> Iterator for StreamScanner<R> {
+    type Item = io::Result<Node>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pending.is_empty() && !self.end_of_input {
+            if let Err(error) = self.fill() {
+                self.end_of_input = true;
+                return Some(Err(error));
+            }
+        }
+
+        self.pending.pop_front().map(Ok)
+    }
+}
+
+impl Executor {
+    /// Runs every instruction of the stream; the enabled state carries over
+    /// from one chunk to the next.
+    pub fn run_stream<R: Read>(&mut self, scanner: StreamScanner<R>) -> Result<i64, StreamError> {
+        for node in scanner {
+            self.step(&node.map_err(StreamError::Io)?)
+                .map_err(StreamError::Execution)?;
+        }
+        Ok(self.sum())
+    }
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamError::Io(error) => write!(f, "cannot read memory dump: {error}"),
+            StreamError::Execution(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl Error for StreamError {}
+
+#[cfg(test)]
+mod test {
+    use crate::{executor::Executor, program::Parser};
+
+    const MEMORY: &str =
+        "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))mul(123,456)";
+
+    #[test]
+    fn should_find_same_instructions_for_every_chunk_size() {
+        let parser = Parser::new();
+        let expected = parser.parse(MEMORY).nodes;
+
+        for chunk_size in 1..=MEMORY.len() {
+            let nodes = parser
+                .scan(MEMORY.as_bytes())
+                .chunk_size(chunk_size)
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+
+            assert_eq!(nodes, expected, "chunk size {chunk_size}");
+        }
+    }
+
+    #[test]
+    fn should_carry_state_across_chunks() {
+        for chunk_size in 1..=8 {
+            let scanner = Parser::new().scan(MEMORY.as_bytes()).chunk_size(chunk_size);
+
+            assert_eq!(Executor::new().run_stream(scanner).unwrap(), 56136);
+        }
+    }
+
+    #[test]
+    fn should_not_match_corrupt_instruction_across_chunks() {
+        let nodes = Parser::new()
+            .scan("mul(12,3456)mul(1,2)".as_bytes())
+            .chunk_size(5)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(nodes.len(), 1);
+        assert_eq!(nodes[0].span, 12..20);
+    }
+}
@@ -0,0 +1,43 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use day4::grid::{Direction, Grid};
+
+/// A square grid of X, M, A and S drawn from a fixed pseudo-random sequence.
+fn synthetic_grid(side: usize) -> Grid<u8> {
+    let mut seed: u64 = 0x2024_0004;
+    let rows = (0..side)
+        .map(|_| {
+            (0..side)
+                .map(|_| {
+                    seed = seed
+                        .wrapping_mul(6364136223846793005)
+                        .wrapping_add(1442695040888963407);
+                    b"XMAS"[(seed >> 62) as usize]
+                })
+                .collect()
+        })
+        .collect();
+
+    Grid::from_rows(rows).unwrap()
+}
+
+fn xmas_search(c: &mut Criterion) {
+    let mut group = c.benchmark_group("xmas_search");
+    group.sample_size(10);
+
+    for side in [1_000, 10_000] {
+        let bytes = synthetic_grid(side);
+        let chars = bytes.map(|&cell| cell as char);
+
+        group.bench_with_input(BenchmarkId::new("find_word", side), &chars, |b, grid| {
+            b.iter(|| grid.find_word(black_box("XMAS"), &Direction::ALL).len())
+        });
+        group.bench_with_input(BenchmarkId::new("count_word", side), &bytes, |b, grid| {
+            b.iter(|| grid.count_word(black_box(b"XMAS"), &Direction::ALL))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, xmas_search);
+criterion_main!(benches);
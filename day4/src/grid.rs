@@ -0,0 +1,651 @@
+use std::{
+    error::Error,
+    fmt,
+    ops::{Index, IndexMut},
+    str::FromStr,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Drop the `\r` that Windows line endings leave at the end of rows.
+    pub strip_carriage_returns: bool,
+}
+
+/// A rectangular grid of cells stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T = char> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+    topology: Topology,
+}
+
+/// What lies past the edges of a grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Topology {
+    /// Nothing: moves that leave the grid fail.
+    #[default]
+    Bounded,
+    /// The opposite edge: the grid wraps around in both axes.
+    Torus,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GridError {
+    /// There is no cell at all.
+    Empty,
+    /// The row on this line (counting from 1) is not as long as the first.
+    Ragged {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// This character cannot be turned into a cell.
+    InvalidCell { line: usize, column: usize },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Position {
+    pub x: usize,
+    pub y: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 8] = [
+        Direction::Up,
+        Direction::UpRight,
+        Direction::Right,
+        Direction::DownRight,
+        Direction::Down,
+        Direction::DownLeft,
+        Direction::Left,
+        Direction::UpLeft,
+    ];
+
+    pub fn offset(&self) -> (isize, isize) {
+        match self {
+            Direction::Up => (0, -1),
+            Direction::UpRight => (1, -1),
+            Direction::Right => (1, 0),
+            Direction::DownRight => (1, 1),
+            Direction::Down => (0, 1),
+            Direction::DownLeft => (-1, 1),
+            Direction::Left => (-1, 0),
+            Direction::UpLeft => (-1, -1),
+        }
+    }
+
+    pub fn opposite(&self) -> Direction {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::UpRight => Direction::DownLeft,
+            Direction::Right => Direction::Left,
+            Direction::DownRight => Direction::UpLeft,
+            Direction::Down => Direction::Up,
+            Direction::DownLeft => Direction::UpRight,
+            Direction::Left => Direction::Right,
+            Direction::UpLeft => Direction::DownRight,
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Grid<T>, GridError> {
+        if rows.iter().all(Vec::is_empty) {
+            return Err(GridError::Empty);
+        }
+        let width = rows[0].len();
+        let height = rows.len();
+        let mut cells = Vec::with_capacity(width * height);
+
+        for (index, row) in rows.into_iter().enumerate() {
+            if row.len() != width {
+                return Err(GridError::Ragged {
+                    line: index + 1,
+                    expected: width,
+                    found: row.len(),
+                });
+            }
+            cells.extend(row);
+        }
+
+        Ok(Grid {
+            cells,
+            width,
+            height,
+            topology: Topology::Bounded,
+        })
+    }
+
+    pub fn with_topology(mut self, topology: Topology) -> Grid<T> {
+        self.topology = topology;
+        self
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, position: Position) -> Option<&T> {
+        (position.x < self.width && position.y < self.height)
+            .then(|| &self.cells[position.y * self.width + position.x])
+    }
+
+    pub fn get_mut(&mut self, position: Position) -> Option<&mut T> {
+        (position.x < self.width && position.y < self.height)
+            .then(|| &mut self.cells[position.y * self.width + position.x])
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width)
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        (0..self.height).filter_map(move |y| self.get(Position { x, y }))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    /// Every cell with its position, row by row.
+    pub fn cells(&self) -> impl Iterator<Item = (Position, &T)> {
+        self.cells.iter().enumerate().map(|(index, cell)| {
+            (
+                Position {
+                    x: index % self.width,
+                    y: index / self.width,
+                },
+                cell,
+            )
+        })
+    }
+
+    /// All cells, row after row.
+    pub fn as_slice(&self) -> &[T] {
+        &self.cells
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+            topology: self.topology,
+        }
+    }
+
+    /// The position `steps` cells away in `direction`, if still inside, or
+    /// wrapped around on a torus.
+    pub fn step(&self, position: Position, direction: Direction, steps: usize) -> Option<Position> {
+        let (dx, dy) = direction.offset();
+        let x = shift(position.x, dx, steps, self.width, self.topology)?;
+        let y = shift(position.y, dy, steps, self.height, self.topology)?;
+
+        (x < self.width && y < self.height).then_some(Position { x, y })
+    }
+
+    /// Up to `len` positions from `start` towards `direction`, stopping at
+    /// the edge, or on a torus before coming back to `start`. `start` is the
+    /// first one.
+    pub fn ray(&self, start: Position, direction: Direction, len: usize) -> Ray<'_, T> {
+        Ray {
+            grid: self,
+            origin: start,
+            next: (start.x < self.width && start.y < self.height).then_some(start),
+            direction,
+            remaining: len,
+        }
+    }
+
+    /// Every position from `start` to the edge in `direction`, or on a torus
+    /// until the line comes back to `start`.
+    pub fn line(&self, start: Position, direction: Direction) -> Ray<'_, T> {
+        self.ray(start, direction, usize::MAX)
+    }
+
+    /// The cells next to `position` up, right, down and left. On a small
+    /// torus each one is listed once, and never `position` itself.
+    pub fn neighbours4(&self, position: Position) -> impl Iterator<Item = Position> + '_ {
+        self.neighbours(
+            position,
+            [
+                Direction::Up,
+                Direction::Right,
+                Direction::Down,
+                Direction::Left,
+            ],
+        )
+    }
+
+    /// Same as `neighbours4`, diagonals included.
+    pub fn neighbours8(&self, position: Position) -> impl Iterator<Item = Position> + '_ {
+        self.neighbours(position, Direction::ALL)
+    }
+
+    fn neighbours(
+        &self,
+        position: Position,
+        directions: impl IntoIterator<Item = Direction>,
+    ) -> impl Iterator<Item = Position> {
+        let mut neighbours = vec![];
+        for direction in directions {
+            if let Some(next) = self.step(position, direction, 1) {
+                if next != position && !neighbours.contains(&next) {
+                    neighbours.push(next);
+                }
+            }
+        }
+        neighbours.into_iter()
+    }
+
+    /// The square of side `2 * radius + 1` centred on `center`, row by row,
+    /// cut down to the part inside the grid. On a torus it wraps instead,
+    /// without covering any cell twice.
+    pub fn window(&self, center: Position, radius: usize) -> impl Iterator<Item = Position> {
+        let xs = span(center.x, radius, self.width, self.topology);
+        let ys = span(center.y, radius, self.height, self.topology);
+
+        ys.into_iter()
+            .flat_map(move |y| xs.clone().into_iter().map(move |x| Position { x, y }))
+    }
+}
+
+/// Moves `from` by `steps` times `delta` along an axis of length `len`.
+fn shift(from: usize, delta: isize, steps: usize, len: usize, topology: Topology) -> Option<usize> {
+    match topology {
+        Topology::Bounded => {
+            from.checked_add_signed(delta.checked_mul(isize::try_from(steps).ok()?)?)
+        }
+        Topology::Torus => {
+            let offset = (steps % len) as isize * delta;
+            Some((from as isize + offset).rem_euclid(len as isize) as usize)
+        }
+    }
+}
+
+/// The coordinates within `radius` of `center` along an axis of length `len`.
+fn span(center: usize, radius: usize, len: usize, topology: Topology) -> Vec<usize> {
+    match topology {
+        Topology::Bounded => (center.saturating_sub(radius)
+            ..center.saturating_add(radius.saturating_add(1)).min(len))
+            .collect(),
+        Topology::Torus if radius.saturating_mul(2).saturating_add(1) >= len => (0..len).collect(),
+        Topology::Torus => (0..=2 * radius)
+            .map(|offset| (center + len - radius + offset) % len)
+            .collect(),
+    }
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions {
+            strip_carriage_returns: true,
+        }
+    }
+}
+
+impl<T: TryFrom<char>> Grid<T> {
+    pub fn parse_with(input: &str, options: ParseOptions) -> Result<Grid<T>, GridError> {
+        let input = input.strip_suffix('\n').unwrap_or(input);
+
+        let rows = input
+            .split('\n')
+            .map(|row| {
+                if options.strip_carriage_returns {
+                    row.strip_suffix('\r').unwrap_or(row)
+                } else {
+                    row
+                }
+            })
+            .enumerate()
+            .map(|(line, row)| {
+                row.chars()
+                    .enumerate()
+                    .map(|(column, cell)| {
+                        T::try_from(cell).map_err(|_| GridError::InvalidCell {
+                            line: line + 1,
+                            column: column + 1,
+                        })
+                    })
+                    .collect()
+            })
+            .collect::<Result<Vec<Vec<T>>, GridError>>()?;
+
+        Grid::from_rows(rows)
+    }
+}
+
+impl<T: TryFrom<char>> FromStr for Grid<T> {
+    type Err = GridError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Grid::parse_with(input, ParseOptions::default())
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        self.get(Position { x, y }).unwrap_or_else(|| {
+            panic!(
+                "({x}, {y}) is outside of the {}x{} grid",
+                self.width, self.height
+            )
+        })
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(Position { x, y })
+            .unwrap_or_else(|| panic!("({x}, {y}) is outside of the {width}x{height} grid"))
+    }
+}
+
+impl fmt::Display for GridError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GridError::Empty => write!(f, "the grid has no cell"),
+            GridError::Ragged {
+                line,
+                expected,
+                found,
+            } => {
+                write!(f, "line {line} has {found} cells, expected {expected}")
+            }
+            GridError::InvalidCell { line, column } => {
+                write!(f, "invalid cell at line {line}, column {column}")
+            }
+        }
+    }
+}
+
+impl Error for GridError {}
+
+pub struct Ray<'a, T> {
+    grid: &'a Grid<T>,
+    origin: Position,
+    next: Option<Position>,
+    direction: Direction,
+    remaining: usize,
+}
+
+impl<T> Iterator for Ray<'_, T> {
+    type Item = Position;
+
+    fn next(&mut self) -> Option<Position> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let current = self.next?;
+
+        self.remaining -= 1;
+        self.next = self
+            .grid
+            .step(current, self.direction, 1)
+            .filter(|&next| next != self.origin);
+        Some(current)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        grid::{Direction, Grid, GridError, ParseOptions, Position, Topology},
+        test_support::grid,
+    };
+
+    #[test]
+    fn should_stop_ray_at_left_and_top_edges() {
+        let grid = grid(&["ABCD", "EFGH", "IJKL"]);
+
+        let ray: Vec<Position> = grid
+            .ray(Position { x: 1, y: 2 }, Direction::UpLeft, 4)
+            .collect();
+
+        assert_eq!(ray, vec![Position { x: 1, y: 2 }, Position { x: 0, y: 1 }]);
+        assert_eq!(
+            grid.ray(Position { x: 0, y: 0 }, Direction::Left, 4)
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn should_limit_ray_to_requested_length() {
+        let grid = grid(&["ABCDEF"]);
+
+        let letters: String = grid
+            .ray(Position { x: 1, y: 0 }, Direction::Right, 3)
+            .filter_map(|position| grid.get(position))
+            .copied()
+            .collect();
+
+        assert_eq!(letters, "BCD");
+        assert_eq!(
+            grid.line(Position { x: 1, y: 0 }, Direction::Right).count(),
+            5
+        );
+    }
+
+    #[test]
+    fn should_only_list_neighbours_inside_grid() {
+        let grid = grid(&["ABC", "DEF", "GHI"]);
+
+        assert_eq!(grid.neighbours4(Position { x: 0, y: 0 }).count(), 2);
+        assert_eq!(grid.neighbours8(Position { x: 0, y: 0 }).count(), 3);
+        assert_eq!(grid.neighbours8(Position { x: 1, y: 1 }).count(), 8);
+        assert_eq!(grid.neighbours4(Position { x: 2, y: 1 }).count(), 3);
+    }
+
+    #[test]
+    fn should_clip_window_at_edges() {
+        let grid = grid(&["ABC", "DEF", "GHI"]);
+
+        let window: String = grid
+            .window(Position { x: 0, y: 0 }, 1)
+            .filter_map(|position| grid.get(position))
+            .copied()
+            .collect();
+
+        assert_eq!(window, "ABDE");
+        assert_eq!(grid.window(Position { x: 1, y: 1 }, 5).count(), 9);
+        assert_eq!(grid.window(Position { x: 1, y: 1 }, usize::MAX).count(), 9);
+    }
+
+    #[test]
+    fn should_parse_and_index_grid() {
+        let grid: Grid = "ABC\nDEF".parse().unwrap();
+
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(2, 1)], 'F');
+        assert_eq!(grid.get(Position { x: 1, y: 0 }), Some(&'B'));
+        assert_eq!(grid.get(Position { x: 3, y: 0 }), None);
+    }
+
+    #[test]
+    fn should_parse_any_cell_type() {
+        let grid: Grid<u8> = "ab\ncd".parse().unwrap();
+
+        assert_eq!(grid[(1, 1)], b'd');
+        assert_eq!("a\u{e9}".parse::<Grid<u8>>().unwrap()[(1, 0)], 0xe9);
+        assert_eq!(
+            "ab\nc\u{263a}".parse::<Grid<u8>>(),
+            Err(GridError::InvalidCell { line: 2, column: 2 })
+        );
+    }
+
+    #[test]
+    fn should_reject_rows_of_different_lengths() {
+        assert_eq!(
+            "ABC\nDE\nFGH".parse::<Grid>(),
+            Err(GridError::Ragged {
+                line: 2,
+                expected: 3,
+                found: 2
+            })
+        );
+        assert_eq!(
+            "ABC\nDEF\n\nGHI".parse::<Grid>(),
+            Err(GridError::Ragged {
+                line: 3,
+                expected: 3,
+                found: 0
+            })
+        );
+    }
+
+    #[test]
+    fn should_reject_leading_blank_line_as_ragged() {
+        assert_eq!(
+            "\nABC\nDEF".parse::<Grid>(),
+            Err(GridError::Ragged {
+                line: 2,
+                expected: 0,
+                found: 3
+            })
+        );
+        assert_eq!("\n\n".parse::<Grid>(), Err(GridError::Empty));
+    }
+
+    #[test]
+    fn should_reject_empty_input() {
+        assert_eq!("".parse::<Grid>(), Err(GridError::Empty));
+        assert_eq!("\n".parse::<Grid>(), Err(GridError::Empty));
+        assert_eq!(Grid::<char>::from_rows(vec![]), Err(GridError::Empty));
+    }
+
+    #[test]
+    fn should_strip_windows_line_endings() {
+        let grid: Grid = "AB\r\nCD\r\n".parse().unwrap();
+
+        assert_eq!(grid, "AB\nCD".parse().unwrap());
+    }
+
+    #[test]
+    fn should_keep_carriage_returns_when_asked() {
+        let keep = ParseOptions {
+            strip_carriage_returns: false,
+        };
+
+        let grid: Grid = Grid::parse_with("AB\r\nCD\r\n", keep).unwrap();
+
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid[(2, 1)], '\r');
+        assert_eq!(
+            Grid::<char>::parse_with("AB\r\nCD", keep),
+            Err(GridError::Ragged {
+                line: 2,
+                expected: 3,
+                found: 2
+            })
+        );
+    }
+
+    #[test]
+    fn should_iterate_rows_columns_and_cells() {
+        let grid: Grid = "ABC\nDEF".parse().unwrap();
+
+        assert_eq!(
+            grid.rows()
+                .map(|row| row.iter().collect())
+                .collect::<Vec<String>>(),
+            vec!["ABC", "DEF"]
+        );
+        assert_eq!(
+            grid.columns()
+                .map(|column| column.collect())
+                .collect::<Vec<String>>(),
+            vec!["AD", "BE", "CF"]
+        );
+        assert_eq!(grid.cells().nth(4), Some((Position { x: 1, y: 1 }, &'E')));
+    }
+
+    #[test]
+    fn should_map_cells_keeping_dimensions() {
+        let grid: Grid = "AB\nCD".parse().unwrap();
+
+        let mapped = grid.map(|cell| *cell == 'A' || *cell == 'D');
+
+        assert_eq!(
+            mapped,
+            Grid::from_rows(vec![vec![true, false], vec![false, true]]).unwrap()
+        );
+    }
+
+    #[test]
+    fn should_wrap_rays_and_neighbours_on_torus() {
+        let grid: Grid = "ABC\nDEF\nGHI"
+            .parse::<Grid>()
+            .unwrap()
+            .with_topology(Topology::Torus);
+
+        let ray: String = grid
+            .ray(Position { x: 0, y: 0 }, Direction::UpLeft, 4)
+            .map(|position| grid[(position.x, position.y)])
+            .collect();
+
+        assert_eq!(ray, "AIE");
+        assert_eq!(grid.neighbours8(Position { x: 0, y: 0 }).count(), 8);
+        assert_eq!(
+            grid.line(Position { x: 1, y: 0 }, Direction::Right).count(),
+            3
+        );
+    }
+
+    #[test]
+    fn should_list_each_neighbour_once_on_a_small_torus() {
+        let grid: Grid = "AB".parse::<Grid>().unwrap().with_topology(Topology::Torus);
+
+        assert_eq!(
+            grid.neighbours4(Position { x: 0, y: 0 })
+                .collect::<Vec<_>>(),
+            vec![Position { x: 1, y: 0 }]
+        );
+        assert_eq!(
+            grid.neighbours8(Position { x: 0, y: 0 })
+                .collect::<Vec<_>>(),
+            vec![Position { x: 1, y: 0 }]
+        );
+    }
+
+    #[test]
+    fn should_wrap_window_on_torus_without_repeating_cells() {
+        let grid: Grid = "ABCD\nEFGH\nIJKL\nMNOP"
+            .parse::<Grid>()
+            .unwrap()
+            .with_topology(Topology::Torus);
+
+        let window: String = grid
+            .window(Position { x: 0, y: 0 }, 1)
+            .map(|position| grid[(position.x, position.y)])
+            .collect();
+
+        assert_eq!(window, "PMNDABHEF");
+        assert_eq!(grid.window(Position { x: 0, y: 0 }, 2).count(), 16);
+        assert_eq!(grid.window(Position { x: 0, y: 0 }, usize::MAX).count(), 16);
+    }
+}
@@ -0,0 +1,10 @@
+pub mod byte_search;
+pub mod grid;
+pub mod multi_search;
+pub mod render;
+pub mod search;
+pub mod template;
+pub mod transform;
+
+#[cfg(test)]
+mod test_support;
@@ -0,0 +1,248 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::{
+    grid::{Direction, Grid, Position, Topology},
+    search::Match,
+};
+
+/// Aho-Corasick automaton over the words and their reversals, so that one
+/// forward pass along a line also finds the words read backwards.
+struct Automaton {
+    nodes: Vec<AutomatonNode>,
+    patterns: Vec<Pattern>,
+}
+
+#[derive(Default)]
+struct AutomatonNode {
+    next: HashMap<char, usize>,
+    fail: usize,
+    outputs: Vec<usize>,
+}
+
+struct Pattern {
+    word: usize,
+    len: usize,
+    reversed: bool,
+}
+
+impl Automaton {
+    fn new(words: &[Vec<char>]) -> Automaton {
+        let mut automaton = Automaton {
+            nodes: vec![AutomatonNode::default()],
+            patterns: vec![],
+        };
+
+        for (word, letters) in words.iter().enumerate() {
+            automaton.insert(letters.iter().copied(), word, false);
+            automaton.insert(letters.iter().rev().copied(), word, true);
+        }
+        automaton.link();
+
+        automaton
+    }
+
+    fn insert(
+        &mut self,
+        letters: impl ExactSizeIterator<Item = char>,
+        word: usize,
+        reversed: bool,
+    ) {
+        let len = letters.len();
+        let mut node = 0;
+        for letter in letters {
+            node = match self.nodes[node].next.get(&letter) {
+                Some(&next) => next,
+                None => {
+                    self.nodes.push(AutomatonNode::default());
+                    let next = self.nodes.len() - 1;
+                    self.nodes[node].next.insert(letter, next);
+                    next
+                }
+            };
+        }
+
+        self.nodes[node].outputs.push(self.patterns.len());
+        self.patterns.push(Pattern {
+            word,
+            len,
+            reversed,
+        });
+    }
+
+    fn link(&mut self) {
+        let mut queue: VecDeque<usize> = self.nodes[0].next.values().copied().collect();
+
+        while let Some(node) = queue.pop_front() {
+            let children: Vec<(char, usize)> = self.nodes[node]
+                .next
+                .iter()
+                .map(|(&c, &n)| (c, n))
+                .collect();
+
+            for (letter, child) in children {
+                let mut fail = self.nodes[node].fail;
+                while fail != 0 && !self.nodes[fail].next.contains_key(&letter) {
+                    fail = self.nodes[fail].fail;
+                }
+                let fail = self.nodes[fail]
+                    .next
+                    .get(&letter)
+                    .copied()
+                    .filter(|&target| target != child)
+                    .unwrap_or(0);
+
+                self.nodes[child].fail = fail;
+                let inherited = self.nodes[fail].outputs.clone();
+                self.nodes[child].outputs.extend(inherited);
+                queue.push_back(child);
+            }
+        }
+    }
+
+    fn advance(&self, mut node: usize, letter: char) -> usize {
+        loop {
+            if let Some(&next) = self.nodes[node].next.get(&letter) {
+                return next;
+            }
+            if node == 0 {
+                return 0;
+            }
+            node = self.nodes[node].fail;
+        }
+    }
+}
+
+impl Grid {
+    /// Searches every word at once, in all eight directions, walking each
+    /// row, column and diagonal a single time. Hits are listed per word in
+    /// the same order as `find_word`.
+    ///
+    /// On a torus the lines have no ends to start from, so each word is
+    /// searched on its own instead.
+    pub fn find_words(&self, words: &[&str]) -> HashMap<String, Vec<Match>> {
+        if self.topology() == Topology::Torus {
+            return words
+                .iter()
+                .map(|&word| (String::from(word), self.find_word(word, &Direction::ALL)))
+                .collect();
+        }
+
+        let mut hits: HashMap<String, Vec<Match>> = words
+            .iter()
+            .map(|&word| (String::from(word), vec![]))
+            .collect();
+        let unique: Vec<&String> = hits.keys().filter(|word| !word.is_empty()).collect();
+        let letters: Vec<Vec<char>> = unique.iter().map(|word| word.chars().collect()).collect();
+        let automaton = Automaton::new(&letters);
+
+        let mut found: Vec<(usize, Match)> = vec![];
+        for (start, direction) in self.line_starts() {
+            let mut node = 0;
+            for (index, position) in self.line(start, direction).enumerate() {
+                node = automaton.advance(node, self[(position.x, position.y)]);
+
+                for &pattern in &automaton.nodes[node].outputs {
+                    let pattern = &automaton.patterns[pattern];
+                    let hit = if pattern.reversed {
+                        Match {
+                            start: position,
+                            direction: direction.opposite(),
+                        }
+                    } else {
+                        Match {
+                            start: self
+                                .step(start, direction, index + 1 - pattern.len)
+                                .unwrap(),
+                            direction,
+                        }
+                    };
+                    found.push((pattern.word, hit));
+                }
+            }
+        }
+
+        let unique: Vec<String> = unique.into_iter().cloned().collect();
+        for (word, hit) in found {
+            hits.get_mut(&unique[word]).unwrap().push(hit);
+        }
+        for matches in hits.values_mut() {
+            matches.sort_by_key(|hit| (hit.start.y, hit.start.x, hit.direction));
+        }
+
+        hits
+    }
+
+    /// The first cell of every row, column and diagonal, with the direction
+    /// to walk it in.
+    fn line_starts(&self) -> Vec<(Position, Direction)> {
+        let mut starts = vec![];
+        if self.width() == 0 || self.height() == 0 {
+            return starts;
+        }
+
+        for y in 0..self.height() {
+            starts.push((Position { x: 0, y }, Direction::Right));
+        }
+        for x in 0..self.width() {
+            starts.push((Position { x, y: 0 }, Direction::Down));
+            starts.push((Position { x, y: 0 }, Direction::DownRight));
+            starts.push((Position { x, y: 0 }, Direction::DownLeft));
+        }
+        for y in 1..self.height() {
+            starts.push((Position { x: 0, y }, Direction::DownRight));
+            starts.push((
+                Position {
+                    x: self.width() - 1,
+                    y,
+                },
+                Direction::DownLeft,
+            ));
+        }
+
+        starts
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{grid::Direction, test_support::grid};
+
+    const EXAMPLE: [&str; 10] = [
+        "MMMSXXMASM",
+        "MSAMXMSMSA",
+        "AMXSXMAAMM",
+        "MSAMASMSMX",
+        "XMASAMXAMM",
+        "XXAMMXXAMA",
+        "SMSMSASXSS",
+        "SAXAMASAAA",
+        "MAMMMXMMMM",
+        "MXMXAXMASX",
+    ];
+
+    #[test]
+    fn should_match_single_word_search_for_every_word() {
+        let grid = grid(&EXAMPLE);
+        let words = ["XMAS", "MAS", "SAM", "AMA", "X", "MM", "XMASX", "NOPE"];
+
+        let hits = grid.find_words(&words);
+
+        for word in words {
+            assert_eq!(hits[word], grid.find_word(word, &Direction::ALL), "{word}");
+        }
+        assert_eq!(hits["XMAS"].len(), 18);
+    }
+
+    #[test]
+    fn should_find_overlapping_words_sharing_prefixes() {
+        let grid = grid(&["XMASAMX"]);
+
+        let hits = grid.find_words(&["XMAS", "XMA", "MAS", "AS", "SAMX"]);
+
+        assert_eq!(hits["XMAS"].len(), 2);
+        assert_eq!(hits["XMA"].len(), 2);
+        assert_eq!(hits["MAS"].len(), 2);
+        assert_eq!(hits["AS"].len(), 2);
+        assert_eq!(hits["SAMX"].len(), 2);
+    }
+}
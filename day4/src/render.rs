@@ -0,0 +1,90 @@
+use std::{collections::HashSet, fmt::Display};
+
+use crate::grid::{Grid, Position};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    /// Highlighted cells in bold yellow, the others dimmed.
+    Ansi,
+    /// Highlighted cells as they are, the others replaced by `.`.
+    Plain,
+}
+
+const HIGHLIGHT: &str = "\x1b[1;33m";
+const DIM: &str = "\x1b[2m";
+const RESET: &str = "\x1b[0m";
+
+impl<T: Display> Grid<T> {
+    pub fn render(&self, highlighted: &HashSet<Position>, style: Style) -> String {
+        let mut rendered = String::new();
+
+        for (position, cell) in self.cells() {
+            let cell = match (style, highlighted.contains(&position)) {
+                (Style::Plain, true) => cell.to_string(),
+                (Style::Plain, false) => String::from("."),
+                (Style::Ansi, true) => format!("{HIGHLIGHT}{cell}{RESET}"),
+                (Style::Ansi, false) => format!("{DIM}{cell}{RESET}"),
+            };
+            rendered.push_str(&cell);
+            if position.x + 1 == self.width() {
+                rendered.push('\n');
+            }
+        }
+
+        rendered
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use crate::{
+        grid::{Direction, Grid, Position},
+        render::Style,
+        template::{Symmetry, Template},
+    };
+
+    #[test]
+    fn should_render_xmas_matches_as_in_puzzle() {
+        let grid: Grid = "QQXQQQ\nQSAMXQ\nQAQQAQ\nXMASQS\nQXQQQQ".parse().unwrap();
+
+        let highlighted: HashSet<Position> = grid
+            .find_word("XMAS", &Direction::ALL)
+            .iter()
+            .flat_map(|found| grid.word_positions(found, 4))
+            .collect();
+
+        assert_eq!(
+            grid.render(&highlighted, Style::Plain),
+            "..X...\n.SAMX.\n.A..A.\nXMAS.S\n.X....\n"
+        );
+    }
+
+    #[test]
+    fn should_render_x_mas_matches() {
+        let grid: Grid = "MQS\nQAQ\nMQS\nQQQ".parse().unwrap();
+        let template = Template::parse("M.S/.A./M.S").unwrap();
+
+        let highlighted: HashSet<Position> = grid
+            .match_template(&template, Symmetry::Rotations)
+            .iter()
+            .flat_map(|found| grid.template_positions(found))
+            .collect();
+
+        assert_eq!(
+            grid.render(&highlighted, Style::Plain),
+            "M.S\n.A.\nM.S\n...\n"
+        );
+    }
+
+    #[test]
+    fn should_colour_highlighted_cells_for_terminals() {
+        let grid: Grid = "XQ".parse().unwrap();
+
+        assert_eq!(
+            grid.render(&HashSet::from([Position { x: 0, y: 0 }]), Style::Ansi),
+            "\x1b[1;33mX\x1b[0m\x1b[2mQ\x1b[0m\n"
+        );
+    }
+}
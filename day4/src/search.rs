@@ -0,0 +1,100 @@
+use crate::grid::{Direction, Grid, Position};
+
+/// A word found in the grid, read from `start` towards `direction`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Match {
+    pub start: Position,
+    pub direction: Direction,
+}
+
+impl Grid {
+    pub fn find_word(&self, word: &str, directions: &[Direction]) -> Vec<Match> {
+        let letters: Vec<char> = word.chars().collect();
+        let mut matches = vec![];
+        if letters.is_empty() {
+            return matches;
+        }
+
+        for (start, &cell) in self.cells() {
+            if cell != letters[0] {
+                continue;
+            }
+
+            for &direction in directions {
+                let mut ray = self.ray(start, direction, letters.len());
+                let found = letters.iter().all(|&letter| {
+                    ray.next()
+                        .is_some_and(|position| self.get(position) == Some(&letter))
+                });
+                if found {
+                    matches.push(Match { start, direction });
+                }
+            }
+        }
+
+        matches
+    }
+
+    /// The cells covered by a match of a word of `len` letters.
+    pub fn word_positions(&self, found: &Match, len: usize) -> Vec<Position> {
+        self.ray(found.start, found.direction, len).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        grid::{Direction, Position},
+        search::Match,
+        test_support::grid,
+    };
+
+    #[test]
+    fn should_find_word_with_position_and_direction() {
+        let grid = grid(&["SAMX.", ".A...", "..M..", "...X."]);
+
+        assert_eq!(
+            grid.find_word("XMAS", &Direction::ALL),
+            vec![
+                Match {
+                    start: Position { x: 3, y: 0 },
+                    direction: Direction::Left
+                },
+                Match {
+                    start: Position { x: 3, y: 3 },
+                    direction: Direction::UpLeft
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn should_only_search_requested_directions() {
+        let grid = grid(&["SAMX.", ".A...", "..M..", "...X."]);
+
+        assert_eq!(
+            grid.find_word("XMAS", &[Direction::Left]),
+            vec![Match {
+                start: Position { x: 3, y: 0 },
+                direction: Direction::Left
+            }]
+        );
+        assert!(grid
+            .find_word("XMAS", &[Direction::Right, Direction::Down])
+            .is_empty());
+    }
+
+    #[test]
+    fn should_find_any_word() {
+        let grid = grid(&["CAT", "OAT", "WAS"]);
+
+        assert_eq!(grid.find_word("AA", &Direction::ALL).len(), 4);
+        assert_eq!(
+            grid.find_word("COW", &Direction::ALL),
+            vec![Match {
+                start: Position { x: 0, y: 0 },
+                direction: Direction::Down
+            }]
+        );
+    }
+}
@@ -0,0 +1,266 @@
+use crate::grid::{Direction, Grid, GridError, Position, Topology};
+
+/// A small 2D pattern where `.` matches any cell.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    cells: Grid<Option<char>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Symmetry {
+    None,
+    Rotations,
+    RotationsAndReflections,
+}
+
+/// A template variant found with its top-left corner at `position`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TemplateMatch {
+    pub position: Position,
+    pub variant: usize,
+    /// Where the cells of the variant that are not wildcards lie, relative
+    /// to `position`.
+    pub offsets: Vec<Position>,
+}
+
+impl Template {
+    /// Rows are separated by new lines or `/`, surrounding spaces are ignored
+    /// and short rows are padded with wildcards.
+    pub fn parse(pattern: &str) -> Result<Template, GridError> {
+        let rows: Vec<Vec<Option<char>>> = pattern
+            .split(['\n', '/'])
+            .map(str::trim)
+            .filter(|row| !row.is_empty())
+            .map(|row| {
+                row.chars()
+                    .map(|cell| if cell == '.' { None } else { Some(cell) })
+                    .collect()
+            })
+            .collect();
+
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let rows = rows
+            .into_iter()
+            .map(|mut row| {
+                row.resize(width, None);
+                row
+            })
+            .collect();
+
+        Ok(Template {
+            cells: Grid::from_rows(rows)?,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.cells.width()
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.height()
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<char> {
+        self.cells[(x, y)]
+    }
+
+    /// Quarter turn clockwise.
+    pub fn rotate(&self) -> Template {
+        Template {
+            cells: self.cells.rotate90().grid,
+        }
+    }
+
+    /// Mirror image along the vertical axis.
+    pub fn reflect(&self) -> Template {
+        Template {
+            cells: self.cells.flip_horizontal().grid,
+        }
+    }
+
+    /// The distinct orientations allowed by `symmetry`, starting with the
+    /// template itself.
+    pub fn variants(&self, symmetry: Symmetry) -> Vec<Template> {
+        let mut candidates = vec![self.clone()];
+        if symmetry != Symmetry::None {
+            for _ in 0..3 {
+                candidates.push(candidates.last().unwrap().rotate());
+            }
+        }
+        if symmetry == Symmetry::RotationsAndReflections {
+            let reflections: Vec<Template> = candidates.iter().map(Template::reflect).collect();
+            candidates.extend(reflections);
+        }
+
+        let mut variants: Vec<Template> = vec![];
+        for candidate in candidates {
+            if !variants.contains(&candidate) {
+                variants.push(candidate);
+            }
+        }
+        variants
+    }
+
+    fn matches_at(&self, grid: &Grid, corner: Position) -> bool {
+        let mut row_starts = grid.ray(corner, Direction::Down, self.height());
+
+        self.cells.rows().all(|row| {
+            let Some(row_start) = row_starts.next() else {
+                return false;
+            };
+            let mut cells = grid.ray(row_start, Direction::Right, self.width());
+
+            row.iter().all(|&expected| {
+                cells.next().is_some_and(|position| {
+                    expected.is_none() || grid.get(position).copied() == expected
+                })
+            })
+        })
+    }
+}
+
+impl Grid {
+    /// The grid cells a match lies on, leaving out the wildcards.
+    pub fn template_positions(&self, found: &TemplateMatch) -> Vec<Position> {
+        found
+            .offsets
+            .iter()
+            .filter_map(|offset| {
+                let column = self.step(found.position, Direction::Right, offset.x)?;
+                self.step(column, Direction::Down, offset.y)
+            })
+            .collect()
+    }
+
+    pub fn match_template(&self, template: &Template, symmetry: Symmetry) -> Vec<TemplateMatch> {
+        let mut matches = vec![];
+
+        for (variant, template) in template.variants(symmetry).iter().enumerate() {
+            if template.width() > self.width() || template.height() > self.height() {
+                continue;
+            }
+            let offsets: Vec<Position> = template
+                .cells
+                .cells()
+                .filter(|(_, cell)| cell.is_some())
+                .map(|(offset, _)| offset)
+                .collect();
+            // On a torus every cell can be a top-left corner.
+            let (last_x, last_y) = match self.topology() {
+                Topology::Bounded => (
+                    self.width() - template.width(),
+                    self.height() - template.height(),
+                ),
+                Topology::Torus => (self.width() - 1, self.height() - 1),
+            };
+            for y in 0..=last_y {
+                for x in 0..=last_x {
+                    let position = Position { x, y };
+                    if template.matches_at(self, position) {
+                        matches.push(TemplateMatch {
+                            position,
+                            variant,
+                            offsets: offsets.clone(),
+                        });
+                    }
+                }
+            }
+        }
+
+        matches
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        grid::{GridError, Position},
+        template::{Symmetry, Template, TemplateMatch},
+        test_support::grid,
+    };
+
+    #[test]
+    fn should_parse_template_with_wildcards() {
+        let template = Template::parse("M.S / .A. / M.S").unwrap();
+
+        assert_eq!((template.width(), template.height()), (3, 3));
+        assert_eq!(template.get(0, 0), Some('M'));
+        assert_eq!(template.get(1, 0), None);
+        assert_eq!(template.get(1, 1), Some('A'));
+    }
+
+    #[test]
+    fn should_keep_only_distinct_variants() {
+        let x_mas = Template::parse("M.S/.A./M.S").unwrap();
+        let corner = Template::parse("AB/C.").unwrap();
+
+        assert_eq!(x_mas.variants(Symmetry::None).len(), 1);
+        assert_eq!(x_mas.variants(Symmetry::Rotations).len(), 4);
+        assert_eq!(x_mas.variants(Symmetry::RotationsAndReflections).len(), 4);
+        assert_eq!(corner.variants(Symmetry::RotationsAndReflections).len(), 8);
+    }
+
+    #[test]
+    fn should_match_template_everywhere_in_grid() {
+        let grid = grid(&["M.S.M", ".A.A.", "M.S.M"]);
+        let offsets = vec![
+            Position { x: 0, y: 0 },
+            Position { x: 2, y: 0 },
+            Position { x: 1, y: 1 },
+            Position { x: 0, y: 2 },
+            Position { x: 2, y: 2 },
+        ];
+
+        assert_eq!(
+            grid.match_template(
+                &Template::parse("M.S/.A./M.S").unwrap(),
+                Symmetry::Rotations
+            ),
+            vec![
+                TemplateMatch {
+                    position: Position { x: 0, y: 0 },
+                    variant: 0,
+                    offsets: offsets.clone(),
+                },
+                TemplateMatch {
+                    position: Position { x: 2, y: 0 },
+                    variant: 2,
+                    offsets,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn should_list_the_cells_of_the_matched_variant() {
+        let grid = grid(&["..#", "..#", "###"]);
+        let corner = Template::parse("#.. / #.. / ###").unwrap();
+
+        let found = grid.match_template(&corner, Symmetry::Rotations);
+
+        assert_eq!(
+            grid.template_positions(&found[0]),
+            vec![
+                Position { x: 2, y: 0 },
+                Position { x: 2, y: 1 },
+                Position { x: 0, y: 2 },
+                Position { x: 1, y: 2 },
+                Position { x: 2, y: 2 },
+            ]
+        );
+    }
+
+    #[test]
+    fn should_match_rotated_shape_without_new_code() {
+        let grid = grid(&["..#", "..#", "###"]);
+        let corner = Template::parse("#.. / #.. / ###").unwrap();
+
+        assert!(grid.match_template(&corner, Symmetry::None).is_empty());
+        assert_eq!(grid.match_template(&corner, Symmetry::Rotations).len(), 1);
+    }
+
+    #[test]
+    fn should_reject_empty_template() {
+        assert_eq!(Template::parse(" / "), Err(GridError::Empty));
+    }
+}
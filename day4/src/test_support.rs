@@ -0,0 +1,6 @@
+use crate::grid::Grid;
+
+/// Builds a grid from its rows, so tests can lay them out one per line.
+pub fn grid(rows: &[&str]) -> Grid {
+    rows.join("\n").parse().unwrap()
+}
@@ -0,0 +1,288 @@
+use crate::{
+    grid::{Direction, Grid, Position, Topology},
+    search::Match,
+};
+
+/// Where the cells of a transformed grid come from: the cell at `(x, y)` is
+/// the original cell at `offset + x * x_axis + y * y_axis`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Transform {
+    offset: (isize, isize),
+    x_axis: (isize, isize),
+    y_axis: (isize, isize),
+}
+
+/// A grid seen from another orientation, along with the way back to the
+/// grid it was made from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transformed<T> {
+    pub grid: Grid<T>,
+    pub transform: Transform,
+}
+
+impl Transform {
+    pub const IDENTITY: Transform = Transform {
+        offset: (0, 0),
+        x_axis: (1, 0),
+        y_axis: (0, 1),
+    };
+
+    /// The original position of `position`.
+    pub fn position(&self, position: Position) -> Position {
+        let (x, y) = self.vector((position.x as isize, position.y as isize));
+        Position {
+            x: (self.offset.0 + x) as usize,
+            y: (self.offset.1 + y) as usize,
+        }
+    }
+
+    /// The original direction of `direction`.
+    pub fn direction(&self, direction: Direction) -> Direction {
+        let offset = self.vector(direction.offset());
+        Direction::ALL
+            .into_iter()
+            .find(|candidate| candidate.offset() == offset)
+            .unwrap()
+    }
+
+    /// The same word as `found`, read in the original grid.
+    pub fn word_match(&self, found: &Match) -> Match {
+        Match {
+            start: self.position(found.start),
+            direction: self.direction(found.direction),
+        }
+    }
+
+    /// Applies `inner` first, then `self`, to get back to the original.
+    fn compose(&self, inner: Transform) -> Transform {
+        let (x, y) = self.vector(inner.offset);
+        Transform {
+            offset: (self.offset.0 + x, self.offset.1 + y),
+            x_axis: self.vector(inner.x_axis),
+            y_axis: self.vector(inner.y_axis),
+        }
+    }
+
+    fn vector(&self, (x, y): (isize, isize)) -> (isize, isize) {
+        (
+            x * self.x_axis.0 + y * self.y_axis.0,
+            x * self.x_axis.1 + y * self.y_axis.1,
+        )
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// Quarter turn clockwise.
+    pub fn rotate90(&self) -> Transformed<T> {
+        let transform = Transform {
+            offset: (0, self.height() as isize - 1),
+            x_axis: (0, -1),
+            y_axis: (1, 0),
+        };
+        self.transformed(transform, self.height(), self.width(), self.topology())
+    }
+
+    /// Rows become columns.
+    pub fn transpose(&self) -> Transformed<T> {
+        let transform = Transform {
+            offset: (0, 0),
+            x_axis: (0, 1),
+            y_axis: (1, 0),
+        };
+        self.transformed(transform, self.height(), self.width(), self.topology())
+    }
+
+    /// Mirror image along the vertical axis: left and right swap.
+    pub fn flip_horizontal(&self) -> Transformed<T> {
+        let transform = Transform {
+            offset: (self.width() as isize - 1, 0),
+            x_axis: (-1, 0),
+            y_axis: (0, 1),
+        };
+        self.transformed(transform, self.width(), self.height(), self.topology())
+    }
+
+    /// Mirror image along the horizontal axis: top and bottom swap.
+    pub fn flip_vertical(&self) -> Transformed<T> {
+        let transform = Transform {
+            offset: (0, self.height() as isize - 1),
+            x_axis: (1, 0),
+            y_axis: (0, -1),
+        };
+        self.transformed(transform, self.width(), self.height(), self.topology())
+    }
+
+    /// The `width` by `height` part of the grid whose top-left corner is
+    /// `corner`, or `None` if it is empty or does not fit. The crop no longer
+    /// wraps, even when cut from a torus.
+    pub fn crop(&self, corner: Position, width: usize, height: usize) -> Option<Transformed<T>> {
+        if width == 0
+            || height == 0
+            || corner
+                .x
+                .checked_add(width)
+                .is_none_or(|end| end > self.width())
+            || corner
+                .y
+                .checked_add(height)
+                .is_none_or(|end| end > self.height())
+        {
+            return None;
+        }
+
+        let transform = Transform {
+            offset: (corner.x as isize, corner.y as isize),
+            ..Transform::IDENTITY
+        };
+        Some(self.transformed(transform, width, height, Topology::Bounded))
+    }
+
+    fn transformed(
+        &self,
+        transform: Transform,
+        width: usize,
+        height: usize,
+        topology: Topology,
+    ) -> Transformed<T> {
+        let rows = (0..height)
+            .map(|y| {
+                (0..width)
+                    .map(|x| {
+                        let original = transform.position(Position { x, y });
+                        self[(original.x, original.y)].clone()
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Transformed {
+            grid: Grid::from_rows(rows).unwrap().with_topology(topology),
+            transform,
+        }
+    }
+}
+
+impl<T: Clone> Transformed<T> {
+    pub fn rotate90(&self) -> Transformed<T> {
+        self.then(self.grid.rotate90())
+    }
+
+    pub fn transpose(&self) -> Transformed<T> {
+        self.then(self.grid.transpose())
+    }
+
+    pub fn flip_horizontal(&self) -> Transformed<T> {
+        self.then(self.grid.flip_horizontal())
+    }
+
+    pub fn flip_vertical(&self) -> Transformed<T> {
+        self.then(self.grid.flip_vertical())
+    }
+
+    pub fn crop(&self, corner: Position, width: usize, height: usize) -> Option<Transformed<T>> {
+        self.grid
+            .crop(corner, width, height)
+            .map(|cropped| self.then(cropped))
+    }
+
+    fn then(&self, next: Transformed<T>) -> Transformed<T> {
+        Transformed {
+            grid: next.grid,
+            transform: self.transform.compose(next.transform),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use crate::{
+        grid::{Direction, Grid, Position},
+        search::Match,
+        test_support::grid,
+        transform::Transform,
+    };
+
+    fn text(grid: &Grid) -> Vec<String> {
+        grid.rows().map(|row| row.iter().collect()).collect()
+    }
+
+    #[test]
+    fn should_rotate_transpose_and_flip() {
+        let grid = grid(&["ABC", "DEF"]);
+
+        assert_eq!(text(&grid.rotate90().grid), vec!["DA", "EB", "FC"]);
+        assert_eq!(text(&grid.transpose().grid), vec!["AD", "BE", "CF"]);
+        assert_eq!(text(&grid.flip_horizontal().grid), vec!["CBA", "FED"]);
+        assert_eq!(text(&grid.flip_vertical().grid), vec!["DEF", "ABC"]);
+    }
+
+    #[test]
+    fn should_crop_only_inside_the_grid() {
+        let grid = grid(&["ABCD", "EFGH", "IJKL"]);
+
+        let cropped = grid.crop(Position { x: 1, y: 1 }, 3, 2).unwrap();
+
+        assert_eq!(text(&cropped.grid), vec!["FGH", "JKL"]);
+        assert_eq!(
+            cropped.transform.position(Position { x: 0, y: 1 }),
+            Position { x: 1, y: 2 }
+        );
+        assert!(grid.crop(Position { x: 2, y: 0 }, 3, 1).is_none());
+        assert!(grid.crop(Position { x: 0, y: 0 }, 0, 1).is_none());
+        assert!(grid.crop(Position { x: 1, y: 0 }, usize::MAX, 1).is_none());
+        assert!(grid.crop(Position { x: 0, y: 1 }, 1, usize::MAX).is_none());
+    }
+
+    #[test]
+    fn should_come_back_after_four_rotations() {
+        let grid = grid(&["ABC", "DEF"]);
+
+        let rotated = grid.rotate90().rotate90().rotate90().rotate90();
+
+        assert_eq!(rotated.grid, grid);
+        assert_eq!(rotated.transform, Transform::IDENTITY);
+    }
+
+    #[test]
+    fn should_map_cells_back_to_the_original() {
+        let grid = grid(&["ABCD", "EFGH", "IJKL"]);
+        let transformed = grid
+            .rotate90()
+            .flip_vertical()
+            .crop(Position { x: 1, y: 0 }, 2, 3)
+            .unwrap()
+            .transpose();
+
+        for (position, cell) in transformed.grid.cells() {
+            let original = transformed.transform.position(position);
+            assert_eq!(grid.get(original), Some(cell));
+        }
+    }
+
+    #[test]
+    fn should_map_matches_back_to_the_original() {
+        let grid = grid(&["XMASQ", "SQQQM", "AMASA", "MQQQS", "XSAMX"]);
+        let expected: HashSet<Match> = grid
+            .find_word("XMAS", &Direction::ALL)
+            .into_iter()
+            .collect();
+
+        for transformed in [
+            grid.rotate90(),
+            grid.transpose(),
+            grid.flip_horizontal(),
+            grid.flip_vertical().rotate90(),
+        ] {
+            let found: HashSet<Match> = transformed
+                .grid
+                .find_word("XMAS", &Direction::ALL)
+                .iter()
+                .map(|found| transformed.transform.word_match(found))
+                .collect();
+
+            assert_eq!(found, expected);
+        }
+    }
+}
@@ -0,0 +1,302 @@
+use crate::{
+    lab::{Direction, Guard, Lab, Position},
+    loops::Outcome,
+    visited::StateSet,
+};
+
+/// For every cell and direction, where the guard stops in front of the next
+/// obstacle, so that a patrol moves a whole segment at a time.
+pub struct JumpTable {
+    width: usize,
+    height: usize,
+    /// Indexed by `y * width + x`, then by `direction.index()`. `None` when
+    /// nothing stands between the cell and the edge of the lab.
+    stops: Vec<[Option<Position>; 4]>,
+}
+
+impl JumpTable {
+    pub fn new(lab: &Lab) -> JumpTable {
+        let (width, height) = (lab.lab.width(), lab.lab.height());
+        let mut stops = vec![[None; 4]; width * height];
+        let is_obstacle = |x: usize, y: usize| lab.lab[(x, y)] == '#';
+
+        for x in 0..width {
+            let mut stop = None;
+            for y in 0..height {
+                if is_obstacle(x, y) {
+                    stop = Some(Position { x, y: y + 1 });
+                } else {
+                    stops[y * width + x][Direction::UP.index()] = stop;
+                }
+            }
+            let mut stop = None;
+            for y in (0..height).rev() {
+                if is_obstacle(x, y) {
+                    stop = y.checked_sub(1).map(|y| Position { x, y });
+                } else {
+                    stops[y * width + x][Direction::DOWN.index()] = stop;
+                }
+            }
+        }
+        for y in 0..height {
+            let mut stop = None;
+            for x in 0..width {
+                if is_obstacle(x, y) {
+                    stop = Some(Position { x: x + 1, y });
+                } else {
+                    stops[y * width + x][Direction::LEFT.index()] = stop;
+                }
+            }
+            let mut stop = None;
+            for x in (0..width).rev() {
+                if is_obstacle(x, y) {
+                    stop = x.checked_sub(1).map(|x| Position { x, y });
+                } else {
+                    stops[y * width + x][Direction::RIGHT.index()] = stop;
+                }
+            }
+        }
+
+        JumpTable {
+            width,
+            height,
+            stops,
+        }
+    }
+
+    /// Where a guard at `from` facing `direction` stops, or `None` if it
+    /// leaves the lab. `obstruction` is an extra obstacle that is not part of
+    /// the table.
+    pub fn jump(
+        &self,
+        from: Position,
+        direction: Direction,
+        obstruction: Option<Position>,
+    ) -> Option<Position> {
+        let stop = self.stops[from.y * self.width + from.x][direction.index()];
+        let Some(obstruction) = obstruction else {
+            return stop;
+        };
+
+        let ahead = |position: Position| along(from, position, direction);
+        match ahead(obstruction) {
+            Some(distance)
+                if distance >= 1 && stop.is_none_or(|stop| Some(distance) <= ahead(stop)) =>
+            {
+                Some(advance(from, direction, distance - 1))
+            }
+            _ => stop,
+        }
+    }
+
+    /// Same outcome as `Guard::patrol` on the lab with `obstruction` added,
+    /// a loop included: it is reported where the guard first enters the
+    /// cycle, even in the middle of a segment.
+    pub fn patrol(&self, guard: &Guard, obstruction: Option<Position>) -> Outcome {
+        let start = (guard.position, guard.direction);
+        let (mut position, mut direction) = start;
+        let mut corners = StateSet::new(self.width, self.height);
+        let mut segments: Vec<Segment> = vec![];
+        let mut turns_in_a_row = 0;
+
+        loop {
+            let Some(stop) = self.jump(position, direction, obstruction) else {
+                return Outcome::Exited;
+            };
+
+            if stop == position {
+                turns_in_a_row += 1;
+                if turns_in_a_row == 4 {
+                    return Outcome::Trapped { position };
+                }
+            } else {
+                turns_in_a_row = 0;
+                segments.push(Segment {
+                    from: position,
+                    direction,
+                    len: along(position, stop, direction).unwrap(),
+                });
+                position = stop;
+                if !corners.insert((position, direction)) {
+                    let first = segments
+                        .iter()
+                        .position(|segment| segment.end() == (position, direction))
+                        .unwrap();
+                    let (position, direction) =
+                        cycle_entry(start, &segments[..=first], &segments[first + 1..]);
+                    return Outcome::Loop {
+                        position,
+                        direction,
+                    };
+                }
+            }
+            direction = direction.turn();
+        }
+    }
+}
+
+/// The cells a guard walks from `from` in `direction` up to its next stop,
+/// `from` left out.
+struct Segment {
+    from: Position,
+    direction: Direction,
+    len: isize,
+}
+
+impl Segment {
+    fn end(&self) -> (Position, Direction) {
+        (advance(self.from, self.direction, self.len), self.direction)
+    }
+
+    /// The first of its steps, counting from 1, that lands on a step of
+    /// `other`.
+    fn first_overlap(&self, other: &Segment) -> Option<isize> {
+        if other.direction != self.direction {
+            return None;
+        }
+        let offset = along(self.from, other.from, self.direction)?;
+        let first = offset.max(0) + 1;
+        (first <= self.len.min(offset + other.len)).then_some(first)
+    }
+}
+
+/// The first state of the patrol that is part of the cycle, the same one
+/// `Guard::patrol` finds repeated: the start itself, or a cell of one of
+/// the segments leading to the cycle. The last of them ends on it.
+fn cycle_entry(
+    (position, direction): (Position, Direction),
+    lead_in: &[Segment],
+    cycle: &[Segment],
+) -> (Position, Direction) {
+    let on_cycle = cycle.iter().any(|segment| {
+        segment.direction == direction
+            && along(segment.from, position, direction)
+                .is_some_and(|steps| (1..=segment.len).contains(&steps))
+    });
+    if on_cycle {
+        return (position, direction);
+    }
+
+    lead_in
+        .iter()
+        .find_map(|segment| {
+            let steps = cycle
+                .iter()
+                .filter_map(|other| segment.first_overlap(other))
+                .min()?;
+            Some((
+                advance(segment.from, segment.direction, steps),
+                segment.direction,
+            ))
+        })
+        .unwrap()
+}
+
+/// How far ahead of `from` in `direction` lies `to`, or `None` when it is
+/// off that line.
+fn along(from: Position, to: Position, direction: Direction) -> Option<isize> {
+    let (dx, dy) = direction.movement_offset();
+    let on_line = if dx == 0 {
+        to.x == from.x
+    } else {
+        to.y == from.y
+    };
+
+    on_line.then(|| (to.x as isize - from.x as isize) * dx + (to.y as isize - from.y as isize) * dy)
+}
+
+fn advance(from: Position, direction: Direction, steps: isize) -> Position {
+    let (dx, dy) = direction.movement_offset();
+    Position {
+        x: (from.x as isize + dx * steps) as usize,
+        y: (from.y as isize + dy * steps) as usize,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use day4::grid;
+
+    use crate::{
+        jump::JumpTable,
+        lab::{Direction, Lab, Position},
+        loops::Outcome,
+        test_support::{example, lab},
+    };
+
+    #[test]
+    fn should_jump_to_the_cell_before_the_next_obstacle() {
+        let table = JumpTable::new(&example());
+        let start = Position { x: 4, y: 6 };
+
+        assert_eq!(
+            table.jump(start, Direction::UP, None),
+            Some(Position { x: 4, y: 1 })
+        );
+        assert_eq!(table.jump(start, Direction::DOWN, None), None);
+        assert_eq!(
+            table.jump(start, Direction::LEFT, None),
+            Some(Position { x: 2, y: 6 })
+        );
+        assert_eq!(
+            table.jump(start, Direction::UP, Some(Position { x: 4, y: 3 })),
+            Some(Position { x: 4, y: 4 })
+        );
+        assert_eq!(
+            table.jump(start, Direction::DOWN, Some(Position { x: 4, y: 7 })),
+            Some(start)
+        );
+        assert_eq!(
+            table.jump(start, Direction::UP, Some(Position { x: 4, y: 0 })),
+            Some(Position { x: 4, y: 1 })
+        );
+    }
+
+    #[test]
+    fn should_match_step_wise_patrol_for_every_obstruction() {
+        let lab = example();
+        let table = JumpTable::new(&lab);
+        let guard = lab.find_guard().unwrap();
+
+        for (grid::Position { x, y }, &space) in lab.lab.cells() {
+            if space != '.' {
+                continue;
+            }
+            let position = Position { x, y };
+            let mut obstructed = Lab::new(lab.lab.clone());
+            obstructed.lab[(x, y)] = '#';
+
+            let expected = obstructed.find_guard().unwrap().patrol(&obstructed);
+            let found = table.patrol(&guard, Some(position));
+
+            assert_eq!(found, expected, "{position:?}");
+        }
+    }
+
+    #[test]
+    fn should_report_loop_where_the_cycle_starts() {
+        let lab = example();
+        let guard = lab.find_guard().unwrap();
+
+        assert_eq!(
+            JumpTable::new(&lab).patrol(&guard, Some(Position { x: 3, y: 6 })),
+            Outcome::Loop {
+                position: Position { x: 4, y: 5 },
+                direction: Direction::UP,
+            }
+        );
+    }
+
+    #[test]
+    fn should_report_trapped_guard() {
+        let lab = lab(&[".#.", "#^#", ".#."]);
+        let guard = lab.find_guard().unwrap();
+
+        assert_eq!(
+            JumpTable::new(&lab).patrol(&guard, None),
+            Outcome::Trapped {
+                position: Position { x: 1, y: 1 }
+            }
+        );
+    }
+}
@@ -0,0 +1,453 @@
+use std::{error::Error, fmt};
+
+use day4::grid::{self, Grid};
+
+use crate::visited::CellSet;
+
+/// The number of positions the guard visits, whether it ends up leaving the
+/// lab, looping or trapped.
+pub fn patroled_space(lab: &Lab) -> Result<usize, LabError> {
+    let mut guard = lab.find_guard()?;
+    guard.patrol(lab);
+
+    Ok(guard.visited_space.len())
+}
+
+#[derive(Debug, Eq, Hash, PartialEq, Clone, Copy)]
+pub struct Position {
+    pub x: usize,
+    pub y: usize,
+}
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Direction {
+    UP,
+    RIGHT,
+    DOWN,
+    LEFT,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Guard {
+    pub(crate) direction: Direction,
+    pub(crate) position: Position,
+    pub(crate) is_outside_lab: bool,
+    pub(crate) visited_space: CellSet,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LabError {
+    NoGuard,
+    /// Positions of the first two guards, in reading order.
+    MultipleGuards {
+        first: Position,
+        second: Position,
+    },
+}
+
+pub struct Lab {
+    pub(crate) lab: Grid,
+}
+
+impl Lab {
+    pub fn new(lab: Grid) -> Lab {
+        Lab { lab }
+    }
+
+    pub fn find_guard(&self) -> Result<Guard, LabError> {
+        let mut guards = self.find_guards()?.into_iter();
+        let first = guards.next().unwrap();
+
+        match guards.next() {
+            Some(second) => Err(LabError::MultipleGuards {
+                first: first.position,
+                second: second.position,
+            }),
+            None => Ok(first),
+        }
+    }
+
+    /// Every guard in the lab, in reading order.
+    pub fn find_guards(&self) -> Result<Vec<Guard>, LabError> {
+        let guards: Vec<Guard> = self
+            .lab
+            .cells()
+            .filter_map(|(grid::Position { x, y }, &space)| {
+                let direction = Direction::from_glyph(space)?;
+                let position = Position { x, y };
+                let mut visited_space = CellSet::new(self.lab.width(), self.lab.height());
+                visited_space.insert(position);
+
+                Some(Guard {
+                    direction,
+                    position,
+                    is_outside_lab: false,
+                    visited_space,
+                })
+            })
+            .collect();
+
+        if guards.is_empty() {
+            return Err(LabError::NoGuard);
+        }
+        Ok(guards)
+    }
+
+    fn is_inside_lab(&self, (x, y): (isize, isize)) -> bool {
+        0 <= x && (x as usize) < self.lab.width() && 0 <= y && (y as usize) < self.lab.height()
+    }
+
+    fn get_space(&self, (x, y): (usize, usize)) -> Option<&char> {
+        self.lab.get(grid::Position { x, y })
+    }
+}
+
+/// What the guard did on a call to `Guard::r#move`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Movement {
+    Walked,
+    Exited,
+    /// Obstacles on all four sides, the guard cannot go anywhere.
+    Trapped,
+}
+
+/// What the guard did on a call to `Guard::step`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    Walked,
+    Turned,
+    Exited,
+}
+
+impl Guard {
+    /// Turns right until the way ahead is free, then takes one step.
+    pub fn r#move(&mut self, lab: &Lab) -> Movement {
+        for _ in 0..4 {
+            match self.step(lab) {
+                Step::Walked => return Movement::Walked,
+                Step::Turned => {}
+                Step::Exited => return Movement::Exited,
+            }
+        }
+
+        Movement::Trapped
+    }
+
+    /// Takes one step forward, or turns right if an obstacle is in the way.
+    pub fn step(&mut self, lab: &Lab) -> Step {
+        let (dx, dy) = Direction::movement_offset(&self.direction);
+
+        let new_x = self.position.x as isize + dx;
+        let new_y = self.position.y as isize + dy;
+
+        if !lab.is_inside_lab((new_x, new_y)) {
+            self.is_outside_lab = true;
+            return Step::Exited;
+        }
+
+        let new_x = new_x as usize;
+        let new_y = new_y as usize;
+
+        if lab.get_space((new_x, new_y)) != Some(&'#') {
+            let new_pos = Position { x: new_x, y: new_y };
+            self.position = new_pos;
+            self.visited_space.insert(new_pos);
+            Step::Walked
+        } else {
+            self.direction = self.direction.turn();
+            Step::Turned
+        }
+    }
+}
+
+impl Direction {
+    /// Every direction, clockwise from `UP`.
+    pub(crate) const ALL: [Direction; 4] = [
+        Direction::UP,
+        Direction::RIGHT,
+        Direction::DOWN,
+        Direction::LEFT,
+    ];
+
+    /// The direction a guard drawn as `glyph` is facing.
+    fn from_glyph(glyph: char) -> Option<Direction> {
+        match glyph {
+            '^' => Some(Direction::UP),
+            '>' => Some(Direction::RIGHT),
+            'v' => Some(Direction::DOWN),
+            '<' => Some(Direction::LEFT),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn turn(&self) -> Self {
+        match *self {
+            Self::UP => Self::RIGHT,
+            Self::RIGHT => Self::DOWN,
+            Self::DOWN => Self::LEFT,
+            Self::LEFT => Self::UP,
+        }
+    }
+
+    /// Where the direction comes in `ALL`.
+    pub(crate) fn index(&self) -> usize {
+        match self {
+            Direction::UP => 0,
+            Direction::RIGHT => 1,
+            Direction::DOWN => 2,
+            Direction::LEFT => 3,
+        }
+    }
+
+    pub(crate) fn movement_offset(&self) -> (isize, isize) {
+        match self {
+            Direction::UP => (0, -1),
+            Direction::DOWN => (0, 1),
+            Direction::LEFT => (-1, 0),
+            Direction::RIGHT => (1, 0),
+        }
+    }
+}
+
+impl fmt::Display for LabError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LabError::NoGuard => write!(f, "the lab has no guard"),
+            LabError::MultipleGuards { first, second } => write!(
+                f,
+                "the lab has several guards, at ({}, {}) and ({}, {})",
+                first.x, first.y, second.x, second.y
+            ),
+        }
+    }
+}
+
+impl Error for LabError {}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use day4::grid::Grid;
+
+    use crate::{
+        lab::{Direction, Guard, Lab, LabError, Movement, Position},
+        visited::CellSet,
+    };
+
+    fn visited(width: usize, height: usize, positions: &[Position]) -> CellSet {
+        let mut visited = CellSet::new(width, height);
+        visited.extend(positions.iter().copied());
+        visited
+    }
+
+    #[test]
+    fn should_extract_guard_position() {
+        let lab = Lab {
+            lab: Grid::from_rows(vec![
+                vec!['#', '.', '.', '.', '.'],
+                vec!['.', '.', '#', '^', '.'],
+                vec!['#', '.', '.', '#', '.'],
+            ])
+            .unwrap(),
+        };
+
+        let guard = lab.find_guard().unwrap();
+
+        assert_eq!(
+            guard,
+            Guard {
+                direction: Direction::UP,
+                position: Position { x: 3, y: 1 },
+                is_outside_lab: false,
+                visited_space: visited(5, 3, &[Position { x: 3, y: 1 }]),
+            }
+        );
+    }
+
+    #[test]
+    fn should_move_up_until_obstacle() {
+        // Given
+        let lab = Lab {
+            lab: Grid::from_rows(vec![
+                vec!['#', '#', '.', '.', '.'],
+                vec!['.', '.', '.', '.', '.'],
+                vec!['#', '^', '.', '#', '.'],
+            ])
+            .unwrap(),
+        };
+        let mut guard = Guard {
+            direction: Direction::UP,
+            position: Position { x: 1, y: 2 },
+            is_outside_lab: false,
+            visited_space: CellSet::new(5, 3),
+        };
+
+        // When
+        guard.r#move(&lab);
+        guard.r#move(&lab);
+
+        // Then
+        assert_eq!(guard.position.y, 1);
+    }
+
+    #[test]
+    fn should_turn_right_if_obstacle_and_move() {
+        // Given
+        let lab = Lab {
+            lab: Grid::from_rows(vec![
+                vec!['#', '#', '.', '.', '.'],
+                vec!['.', '.', '.', '.', '.'],
+                vec!['#', '^', '.', '#', '.'],
+            ])
+            .unwrap(),
+        };
+        let mut guard = Guard {
+            direction: Direction::UP,
+            position: Position { x: 1, y: 2 },
+            is_outside_lab: false,
+            visited_space: CellSet::new(5, 3),
+        };
+
+        // When
+        guard.r#move(&lab);
+        guard.r#move(&lab);
+
+        // Then
+        assert_eq!(guard.position, Position { x: 2, y: 1 });
+    }
+
+    #[test]
+    fn should_detect_when_going_out_of_lab_upside() {
+        // Given
+        let lab = Lab {
+            lab: Grid::from_rows(vec![vec!['#', '^', '.', '.']]).unwrap(),
+        };
+        let mut guard = Guard {
+            direction: Direction::UP,
+            position: Position { x: 1, y: 0 },
+            is_outside_lab: false,
+            visited_space: CellSet::new(4, 1),
+        };
+
+        // When
+        guard.r#move(&lab);
+
+        // Then
+        assert!(guard.is_outside_lab);
+    }
+
+    #[test]
+    fn should_detect_when_going_out_of_lab_rightside() {
+        // Given
+        let lab = Lab {
+            lab: Grid::from_rows(vec![vec!['#', '.', '.', '.'], vec!['#', '.', '.', '>']]).unwrap(),
+        };
+        let mut guard = Guard {
+            direction: Direction::RIGHT,
+            position: Position { x: 3, y: 1 },
+            is_outside_lab: false,
+            visited_space: CellSet::new(4, 2),
+        };
+
+        // When
+        guard.r#move(&lab);
+
+        // Then
+        assert!(guard.is_outside_lab);
+    }
+
+    #[test]
+    fn should_store_visited_space() {
+        // Given
+        let lab = Lab {
+            lab: Grid::from_rows(vec![
+                vec!['#', '#', '.', '.', '.'],
+                vec!['.', '.', '.', '.', '.'],
+                vec!['#', '^', '.', '#', '.'],
+            ])
+            .unwrap(),
+        };
+        let mut guard = Guard {
+            direction: Direction::UP,
+            position: Position { x: 1, y: 2 },
+            is_outside_lab: false,
+            visited_space: visited(5, 3, &[Position { x: 1, y: 2 }]),
+        };
+
+        // When
+        guard.r#move(&lab);
+        guard.r#move(&lab);
+
+        // Then
+        assert_eq!(
+            guard.visited_space,
+            HashSet::from([
+                Position { x: 1, y: 2 },
+                Position { x: 1, y: 1 },
+                Position { x: 2, y: 1 }
+            ])
+        );
+    }
+
+    #[test]
+    fn should_extract_guard_direction_from_glyph() {
+        for (glyph, direction) in [
+            ('^', Direction::UP),
+            ('>', Direction::RIGHT),
+            ('v', Direction::DOWN),
+            ('<', Direction::LEFT),
+        ] {
+            let lab = Lab {
+                lab: Grid::from_rows(vec![vec!['#', '.'], vec!['.', glyph]]).unwrap(),
+            };
+
+            let guard = lab.find_guard().unwrap();
+
+            assert_eq!(guard.direction, direction);
+            assert_eq!(guard.position, Position { x: 1, y: 1 });
+        }
+    }
+
+    #[test]
+    fn should_fail_without_guard() {
+        let lab = Lab {
+            lab: Grid::from_rows(vec![vec!['#', '.', '.']]).unwrap(),
+        };
+
+        assert_eq!(lab.find_guard(), Err(LabError::NoGuard));
+    }
+
+    #[test]
+    fn should_fail_with_several_guards() {
+        let lab = Lab {
+            lab: Grid::from_rows(vec![vec!['^', '.'], vec!['#', '<']]).unwrap(),
+        };
+
+        assert_eq!(
+            lab.find_guard(),
+            Err(LabError::MultipleGuards {
+                first: Position { x: 0, y: 0 },
+                second: Position { x: 1, y: 1 },
+            })
+        );
+    }
+
+    #[test]
+    fn should_report_trapped_guard_instead_of_turning_forever() {
+        let lab = Lab {
+            lab: Grid::from_rows(vec![
+                vec!['.', '#', '.'],
+                vec!['#', '^', '#'],
+                vec!['.', '#', '.'],
+            ])
+            .unwrap(),
+        };
+        let mut guard = lab.find_guard().unwrap();
+
+        assert_eq!(guard.r#move(&lab), Movement::Trapped);
+        assert_eq!(guard.position, Position { x: 1, y: 1 });
+        assert_eq!(guard.direction, Direction::UP);
+    }
+}
@@ -0,0 +1,10 @@
+pub mod jump;
+pub mod lab;
+pub mod loops;
+pub mod multi_guard;
+pub mod simulation;
+pub mod trace;
+pub mod visited;
+
+#[cfg(test)]
+mod test_support;
@@ -0,0 +1,154 @@
+use std::collections::HashSet;
+
+use crate::{
+    jump::JumpTable,
+    lab::{Direction, Guard, Lab, LabError, Movement, Position},
+    visited::StateSet,
+};
+
+/// How a patrol ends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Exited,
+    /// The guard came back to `position` facing `direction`, where the cycle
+    /// it now repeats forever starts.
+    Loop {
+        position: Position,
+        direction: Direction,
+    },
+    Trapped {
+        position: Position,
+    },
+}
+
+impl Guard {
+    /// Patrols until the guard leaves the lab, gets stuck, or stands again on
+    /// a position it already reached facing the same way.
+    pub fn patrol(&mut self, lab: &Lab) -> Outcome {
+        self.patrol_with(lab, |_| {})
+    }
+
+    /// Same as `patrol`, calling `on_step` with the guard after every step.
+    pub(crate) fn patrol_with(&mut self, lab: &Lab, mut on_step: impl FnMut(&Guard)) -> Outcome {
+        let mut states = StateSet::new(lab.lab.width(), lab.lab.height());
+        states.insert((self.position, self.direction));
+
+        loop {
+            match self.r#move(lab) {
+                Movement::Exited => return Outcome::Exited,
+                Movement::Trapped => {
+                    return Outcome::Trapped {
+                        position: self.position,
+                    }
+                }
+                Movement::Walked => {
+                    on_step(self);
+                    if !states.insert((self.position, self.direction)) {
+                        return Outcome::Loop {
+                            position: self.position,
+                            direction: self.direction,
+                        };
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn is_looping(&mut self, lab: &Lab) -> bool {
+        matches!(self.patrol(lab), Outcome::Loop { .. })
+    }
+}
+
+/// The positions where one more obstruction makes the guard loop forever.
+///
+/// An obstruction off the original path is never met, so only the cells the
+/// guard patrols are tried, except the one it starts on.
+pub fn loop_obstructions(lab: &Lab) -> Result<HashSet<Position>, LabError> {
+    let start = lab.find_guard()?;
+    let mut guard = start.clone();
+    guard.patrol(lab);
+
+    let table = JumpTable::new(lab);
+    Ok(guard
+        .visited_space
+        .iter()
+        .filter(|&position| position != start.position)
+        .filter(|&position| matches!(table.patrol(&start, Some(position)), Outcome::Loop { .. }))
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use crate::{
+        lab::{patroled_space, Direction, Position},
+        loops::{loop_obstructions, Outcome},
+        test_support::{example, lab},
+    };
+
+    #[test]
+    fn should_patrol_official_example() {
+        assert_eq!(patroled_space(&example()), Ok(41));
+    }
+
+    #[test]
+    fn should_not_loop_in_official_example() {
+        let lab = example();
+
+        assert!(!lab.find_guard().unwrap().is_looping(&lab));
+    }
+
+    #[test]
+    fn should_loop_when_obstruction_closes_the_path() {
+        let mut lab = example();
+        lab.lab[(3, 6)] = '#';
+
+        assert!(lab.find_guard().unwrap().is_looping(&lab));
+    }
+
+    #[test]
+    fn should_find_loop_obstructions_of_official_example() {
+        assert_eq!(
+            loop_obstructions(&example()),
+            Ok(HashSet::from([
+                Position { x: 3, y: 6 },
+                Position { x: 6, y: 7 },
+                Position { x: 7, y: 7 },
+                Position { x: 1, y: 8 },
+                Position { x: 3, y: 8 },
+                Position { x: 7, y: 9 },
+            ]))
+        );
+    }
+
+    #[test]
+    fn should_end_patrol_with_its_outcome() {
+        let trapped = lab(&[".#.", "#^#", ".#."]);
+        let mut lab = example();
+        assert_eq!(lab.find_guard().unwrap().patrol(&lab), Outcome::Exited);
+
+        lab.lab[(3, 6)] = '#';
+        assert_eq!(
+            lab.find_guard().unwrap().patrol(&lab),
+            Outcome::Loop {
+                position: Position { x: 4, y: 5 },
+                direction: Direction::UP,
+            }
+        );
+
+        assert_eq!(
+            trapped.find_guard().unwrap().patrol(&trapped),
+            Outcome::Trapped {
+                position: Position { x: 1, y: 1 }
+            }
+        );
+    }
+
+    #[test]
+    fn should_count_patroled_space_of_looping_lab() {
+        let lab = lab(&[".#..", "...#", "#^..", "..#."]);
+
+        assert_eq!(patroled_space(&lab), Ok(4));
+    }
+}
@@ -0,0 +1,348 @@
+use std::{
+    collections::{HashMap, HashSet},
+    mem,
+};
+
+use day4::grid;
+
+use crate::{
+    lab::{Direction, Guard, Lab, LabError, Movement, Position},
+    loops::Outcome,
+    visited::CellSet,
+};
+
+/// What happens when guards meet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Collision {
+    /// Guards walk through each other.
+    Ignore,
+    /// Guards ending a tick on the same cell, or swapping cells during it,
+    /// stop there for good, and so does any guard walking into them
+    /// afterwards.
+    Stop,
+    /// Guards turn in front of each other like in front of `#`.
+    Obstacle,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Finish {
+    Exited,
+    Collided,
+    /// Cannot ever move again, boxed in by obstacles or by other guards.
+    Trapped,
+    /// Still on its way when the whole lab started repeating itself.
+    Looping,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GuardReport {
+    pub start: Position,
+    pub finish: Finish,
+    pub visited: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    /// One per guard, in reading order of their starting positions.
+    pub guards: Vec<GuardReport>,
+    /// Cells visited by at least one guard.
+    pub combined: usize,
+}
+
+/// Moves every guard in the lab once per tick, in reading order, until none
+/// is left on its way.
+pub fn patrol_all(lab: &Lab, collision: Collision) -> Result<Report, LabError> {
+    let mut guards = lab.find_guards()?;
+    let starts: Vec<Position> = guards.iter().map(|guard| guard.position).collect();
+
+    let finishes = match collision {
+        // Guards cannot affect each other, so each one can patrol alone.
+        Collision::Ignore => guards
+            .iter_mut()
+            .map(|guard| match guard.patrol(lab) {
+                Outcome::Exited => Finish::Exited,
+                Outcome::Loop { .. } => Finish::Looping,
+                Outcome::Trapped { .. } => Finish::Trapped,
+            })
+            .collect(),
+        Collision::Stop | Collision::Obstacle => lockstep(lab, &mut guards, collision),
+    };
+
+    let mut combined = CellSet::new(lab.lab.width(), lab.lab.height());
+    for guard in &guards {
+        combined.extend(guard.visited_space.iter());
+    }
+
+    Ok(Report {
+        guards: guards
+            .iter()
+            .zip(starts)
+            .zip(finishes)
+            .map(|((guard, start), finish)| GuardReport {
+                start,
+                finish,
+                visited: guard.visited_space.len(),
+            })
+            .collect(),
+        combined: combined.len(),
+    })
+}
+
+fn lockstep(lab: &Lab, guards: &mut [Guard], collision: Collision) -> Vec<Finish> {
+    let original = lab;
+    let mut lab = Lab::new(lab.lab.clone());
+    let mut finishes: Vec<Option<Finish>> = vec![None; guards.len()];
+
+    // Brent's cycle detection: the lab repeats itself once it comes back to
+    // the state saved last, which is moved forward after 1, 2, 4... ticks.
+    let mut saved = state(guards, &finishes);
+    let (mut power, mut ticks) = (1, 0);
+    // Guards that have not moved since the state was saved.
+    let mut stuck = vec![true; guards.len()];
+
+    while finishes.iter().any(Option::is_none) {
+        let starts: Vec<Position> = guards.iter().map(|guard| guard.position).collect();
+
+        for index in 0..guards.len() {
+            if finishes[index].is_some() {
+                continue;
+            }
+
+            let others = if collision == Collision::Obstacle {
+                inside(guards, &finishes)
+                    .filter(|&other| other != index)
+                    .map(|other| guards[other].position)
+                    .collect()
+            } else {
+                vec![]
+            };
+            let spaces: Vec<char> = others
+                .iter()
+                .map(|position| mem::replace(&mut lab.lab[(position.x, position.y)], '#'))
+                .collect();
+
+            let movement = guards[index].r#move(&lab);
+            stuck[index] &= movement == Movement::Trapped;
+            finishes[index] = match movement {
+                Movement::Exited => Some(Finish::Exited),
+                // Other guards may still move out of the way.
+                Movement::Trapped if !walled_in(original, guards[index].position) => None,
+                Movement::Trapped => Some(Finish::Trapped),
+                Movement::Walked => None,
+            };
+
+            for (position, space) in others.iter().zip(spaces) {
+                lab.lab[(position.x, position.y)] = space;
+            }
+        }
+
+        if collision == Collision::Stop {
+            let mut occupied: HashMap<Position, usize> = HashMap::new();
+            for index in inside(guards, &finishes) {
+                *occupied.entry(guards[index].position).or_default() += 1;
+            }
+            // Two guards swapping cells walked through each other.
+            let moves: HashSet<(Position, Position)> = inside(guards, &finishes)
+                .map(|index| (starts[index], guards[index].position))
+                .filter(|(start, end)| start != end)
+                .collect();
+            for (index, guard) in guards.iter().enumerate() {
+                let swapped = moves.contains(&(guard.position, starts[index]));
+                if finishes[index].is_none() && (occupied[&guard.position] > 1 || swapped) {
+                    finishes[index] = Some(Finish::Collided);
+                }
+            }
+        }
+
+        let current = state(guards, &finishes);
+        if current == saved {
+            // A guard that did not move over a whole cycle never will.
+            for (finish, &stuck) in finishes.iter_mut().zip(&stuck) {
+                if finish.is_none() {
+                    *finish = Some(if stuck {
+                        Finish::Trapped
+                    } else {
+                        Finish::Looping
+                    });
+                }
+            }
+        }
+        ticks += 1;
+        if ticks == power {
+            saved = current;
+            power *= 2;
+            ticks = 0;
+            stuck.fill(true);
+        }
+    }
+
+    finishes.into_iter().map(Option::unwrap).collect()
+}
+
+/// Everything that decides how the guards move on from here.
+fn state(
+    guards: &[Guard],
+    finishes: &[Option<Finish>],
+) -> Vec<(Position, Direction, Option<Finish>)> {
+    guards
+        .iter()
+        .zip(finishes)
+        .map(|(guard, &finish)| (guard.position, guard.direction, finish))
+        .collect()
+}
+
+/// Whether the lab itself has obstacles on all four sides of `position`.
+fn walled_in(lab: &Lab, position: Position) -> bool {
+    Direction::ALL.iter().all(|direction| {
+        let (dx, dy) = direction.movement_offset();
+        let neighbour = position
+            .x
+            .checked_add_signed(dx)
+            .zip(position.y.checked_add_signed(dy));
+        neighbour.is_some_and(|(x, y)| lab.lab.get(grid::Position { x, y }) == Some(&'#'))
+    })
+}
+
+/// The guards still standing somewhere in the lab.
+fn inside<'a>(
+    guards: &'a [Guard],
+    finishes: &'a [Option<Finish>],
+) -> impl Iterator<Item = usize> + 'a {
+    (0..guards.len()).filter(|&index| finishes[index] != Some(Finish::Exited))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        lab::{patroled_space, Direction, LabError, Position},
+        multi_guard::{patrol_all, Collision, Finish, GuardReport},
+        test_support::lab,
+    };
+
+    #[test]
+    fn should_find_every_guard() {
+        let empty = lab(&["#..."]);
+        let lab = lab(&[".v..", "#..<", "^..."]);
+
+        let guards = lab.find_guards().unwrap();
+
+        assert_eq!(
+            guards
+                .iter()
+                .map(|guard| (guard.position, guard.direction))
+                .collect::<Vec<_>>(),
+            vec![
+                (Position { x: 1, y: 0 }, Direction::DOWN),
+                (Position { x: 3, y: 1 }, Direction::LEFT),
+                (Position { x: 0, y: 2 }, Direction::UP),
+            ]
+        );
+        assert_eq!(empty.find_guards().unwrap_err(), LabError::NoGuard);
+    }
+
+    #[test]
+    fn should_patrol_independently_when_ignoring_collisions() {
+        let lab = lab(&["....#.", ">.....", ".^....", "......"]);
+
+        let report = patrol_all(&lab, Collision::Ignore).unwrap();
+
+        assert_eq!(
+            report.guards,
+            vec![
+                GuardReport {
+                    start: Position { x: 0, y: 1 },
+                    finish: Finish::Exited,
+                    visited: 6,
+                },
+                GuardReport {
+                    start: Position { x: 1, y: 2 },
+                    finish: Finish::Exited,
+                    visited: 3,
+                },
+            ]
+        );
+        assert_eq!(report.combined, 8);
+    }
+
+    #[test]
+    fn should_stop_guards_that_collide() {
+        let lab = lab(&[">...<"]);
+
+        let report = patrol_all(&lab, Collision::Stop).unwrap();
+
+        assert_eq!(
+            report
+                .guards
+                .iter()
+                .map(|guard| (guard.finish, guard.visited))
+                .collect::<Vec<_>>(),
+            vec![(Finish::Collided, 3), (Finish::Collided, 3)]
+        );
+        assert_eq!(report.combined, 5);
+    }
+
+    #[test]
+    fn should_stop_guards_that_swap_cells() {
+        let lab = lab(&[">..<"]);
+
+        let report = patrol_all(&lab, Collision::Stop).unwrap();
+
+        assert_eq!(
+            report
+                .guards
+                .iter()
+                .map(|guard| (guard.finish, guard.visited))
+                .collect::<Vec<_>>(),
+            vec![(Finish::Collided, 3), (Finish::Collided, 3)]
+        );
+    }
+
+    #[test]
+    fn should_turn_in_front_of_other_guards() {
+        let lab = lab(&[">...<"]);
+
+        let report = patrol_all(&lab, Collision::Obstacle).unwrap();
+
+        assert_eq!(
+            report
+                .guards
+                .iter()
+                .map(|guard| (guard.finish, guard.visited))
+                .collect::<Vec<_>>(),
+            vec![(Finish::Exited, 5), (Finish::Exited, 2)]
+        );
+        assert_eq!(report.combined, 5);
+    }
+
+    #[test]
+    fn should_trap_a_guard_walled_in_whatever_the_collisions() {
+        let boxed_in_by_each_other = lab(&[".#.", "#^#", "#v#", ".#."]);
+        let lab = lab(&[".#.", "#^#", ".#."]);
+
+        for collision in [Collision::Ignore, Collision::Stop, Collision::Obstacle] {
+            let report = patrol_all(&lab, collision).unwrap();
+
+            assert_eq!(report.guards[0].finish, Finish::Trapped, "{collision:?}");
+        }
+
+        let report = patrol_all(&boxed_in_by_each_other, Collision::Obstacle).unwrap();
+        assert_eq!(
+            report
+                .guards
+                .iter()
+                .map(|guard| (guard.finish, guard.visited))
+                .collect::<Vec<_>>(),
+            vec![(Finish::Trapped, 1), (Finish::Trapped, 1)]
+        );
+    }
+
+    #[test]
+    fn should_end_when_the_lab_repeats_itself() {
+        let lab = lab(&[".#..", "...#", "#^..", "..#."]);
+
+        let report = patrol_all(&lab, Collision::Stop).unwrap();
+
+        assert_eq!(report.guards[0].finish, Finish::Looping);
+        assert_eq!(Ok(report.combined), patroled_space(&lab));
+    }
+}
@@ -0,0 +1,211 @@
+use crate::lab::{Direction, Guard, Lab, LabError, Position, Step};
+
+/// Where the guard stands after a step, and whether that step was a turn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct State {
+    pub position: Position,
+    pub direction: Direction,
+    pub turned: bool,
+}
+
+/// A patrol run one step at a time: every call to `next` either moves the
+/// guard one cell or turns it. It ends when the guard leaves the lab or is
+/// trapped, and never ends for a looping guard.
+pub struct Simulation<'a> {
+    lab: &'a Lab,
+    guard: Guard,
+    turns_in_a_row: usize,
+    /// One entry per step taken, to undo it.
+    journal: Vec<Undo>,
+}
+
+/// A step of a simulation to come back to, earlier or later than the
+/// current one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Snapshot {
+    steps: usize,
+}
+
+/// How the guard stood before a step, and whether the step visited a new
+/// cell.
+struct Undo {
+    position: Position,
+    direction: Direction,
+    turns_in_a_row: usize,
+    visited_new_cell: bool,
+}
+
+impl<'a> Simulation<'a> {
+    pub fn new(lab: &'a Lab) -> Result<Simulation<'a>, LabError> {
+        Ok(Simulation {
+            lab,
+            guard: lab.find_guard()?,
+            turns_in_a_row: 0,
+            journal: vec![],
+        })
+    }
+
+    pub fn state(&self) -> State {
+        State {
+            position: self.guard.position,
+            direction: self.guard.direction,
+            turned: self.turns_in_a_row > 0,
+        }
+    }
+
+    pub fn guard(&self) -> &Guard {
+        &self.guard
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            steps: self.journal.len(),
+        }
+    }
+
+    /// Goes back to a snapshot of this simulation by undoing the steps
+    /// since, or forward to it by taking them again.
+    pub fn restore(&mut self, snapshot: Snapshot) {
+        while self.journal.len() > snapshot.steps {
+            let undo = self.journal.pop().unwrap();
+            if undo.visited_new_cell {
+                self.guard.visited_space.remove(self.guard.position);
+            }
+            self.guard.position = undo.position;
+            self.guard.direction = undo.direction;
+            self.guard.is_outside_lab = false;
+            self.turns_in_a_row = undo.turns_in_a_row;
+        }
+        while self.journal.len() < snapshot.steps && self.next().is_some() {}
+    }
+}
+
+impl Iterator for Simulation<'_> {
+    type Item = State;
+
+    fn next(&mut self) -> Option<State> {
+        // After four turns in a row the guard faces where it started: trapped.
+        if self.guard.is_outside_lab || self.turns_in_a_row == 4 {
+            return None;
+        }
+
+        let undo = Undo {
+            position: self.guard.position,
+            direction: self.guard.direction,
+            turns_in_a_row: self.turns_in_a_row,
+            visited_new_cell: false,
+        };
+        let visited = self.guard.visited_space.len();
+        let step = self.guard.step(self.lab);
+        self.journal.push(Undo {
+            visited_new_cell: self.guard.visited_space.len() > visited,
+            ..undo
+        });
+
+        match step {
+            Step::Walked => self.turns_in_a_row = 0,
+            Step::Turned => self.turns_in_a_row += 1,
+            Step::Exited => return None,
+        }
+        Some(self.state())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        lab::{Direction, Position},
+        simulation::{Simulation, State},
+        test_support::{example, lab},
+    };
+
+    #[test]
+    fn should_yield_walks_and_turns() {
+        let lab = example();
+
+        let states: Vec<State> = Simulation::new(&lab).unwrap().skip(4).take(3).collect();
+
+        assert_eq!(
+            states,
+            vec![
+                State {
+                    position: Position { x: 4, y: 1 },
+                    direction: Direction::UP,
+                    turned: false,
+                },
+                State {
+                    position: Position { x: 4, y: 1 },
+                    direction: Direction::RIGHT,
+                    turned: true,
+                },
+                State {
+                    position: Position { x: 5, y: 1 },
+                    direction: Direction::RIGHT,
+                    turned: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn should_run_until_the_guard_leaves() {
+        let lab = example();
+        let mut simulation = Simulation::new(&lab).unwrap();
+
+        let last = simulation.by_ref().last().unwrap();
+
+        assert_eq!(last.position, Position { x: 7, y: 9 });
+        assert_eq!(simulation.guard().visited_space.len(), 41);
+        assert_eq!(simulation.next(), None);
+    }
+
+    #[test]
+    fn should_stop_turning_when_trapped() {
+        let lab = lab(&[".#.", "#^#", ".#."]);
+
+        let states: Vec<State> = Simulation::new(&lab).unwrap().collect();
+
+        assert_eq!(states.len(), 4);
+        assert!(states.iter().all(|state| state.turned));
+        assert_eq!(states[3].direction, Direction::UP);
+    }
+
+    #[test]
+    fn should_replay_the_same_steps_after_restore() {
+        let lab = example();
+        let mut simulation = Simulation::new(&lab).unwrap();
+        simulation.by_ref().take(10).for_each(drop);
+
+        let before = simulation.state();
+        let snapshot = simulation.snapshot();
+        let forwards: Vec<State> = simulation.by_ref().take(20).collect();
+        simulation.restore(snapshot);
+
+        assert_eq!(simulation.state(), before);
+        assert_eq!(simulation.take(20).collect::<Vec<State>>(), forwards);
+    }
+
+    #[test]
+    fn should_restore_back_past_the_exit_and_forward_again() {
+        let lab = example();
+        let mut simulation = Simulation::new(&lab).unwrap();
+        let start = simulation.snapshot();
+        simulation.by_ref().take(10).for_each(drop);
+        let early = (simulation.state(), simulation.guard().visited_space.clone());
+        simulation.by_ref().for_each(drop);
+        let end = simulation.snapshot();
+
+        simulation.restore(start);
+        assert_eq!(simulation.guard().visited_space.len(), 1);
+        simulation.restore(end);
+        assert_eq!(simulation.guard().visited_space.len(), 41);
+        assert_eq!(simulation.next(), None);
+
+        simulation.restore(start);
+        simulation.by_ref().take(10).for_each(drop);
+        assert_eq!(
+            (simulation.state(), simulation.guard().visited_space.clone()),
+            early
+        );
+    }
+}
@@ -0,0 +1,26 @@
+use day4::grid::Grid;
+
+use crate::lab::Lab;
+
+/// The lab from the puzzle statement.
+const EXAMPLE: &str = "\
+....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...
+";
+
+pub fn example() -> Lab {
+    Lab::new(EXAMPLE.parse::<Grid>().unwrap())
+}
+
+/// Builds a lab from its rows, so tests can lay them out one per line.
+pub fn lab(rows: &[&str]) -> Lab {
+    Lab::new(rows.join("\n").parse::<Grid>().unwrap())
+}
@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+
+use day4::grid;
+
+use crate::{
+    lab::{Direction, Guard, Lab, Position},
+    loops::Outcome,
+};
+
+/// The route of a guard, in order. A turn is a step that keeps the position
+/// and changes the direction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trace {
+    pub steps: Vec<(Position, Direction)>,
+    pub outcome: Outcome,
+}
+
+impl Guard {
+    pub fn trace(&mut self, lab: &Lab) -> Trace {
+        let mut steps = vec![(self.position, self.direction)];
+
+        let outcome = self.patrol_with(lab, |guard| {
+            turn_towards(&mut steps, guard.direction);
+            steps.push((guard.position, guard.direction));
+        });
+        turn_towards(&mut steps, self.direction);
+
+        Trace { steps, outcome }
+    }
+}
+
+/// Records the turns taking the guard from the way of its last step to
+/// `direction`, one quarter turn at a time.
+fn turn_towards(steps: &mut Vec<(Position, Direction)>, direction: Direction) {
+    let &(position, mut last) = steps.last().unwrap();
+    while last != direction {
+        last = last.turn();
+        steps.push((position, last));
+    }
+}
+
+impl Lab {
+    /// Draws the route the way the puzzle does: `|` and `-` where the guard
+    /// went up or down and left or right, `+` where it did both. The
+    /// starting spot keeps the guard.
+    pub fn render(&self, trace: &Trace) -> String {
+        let mut axes: HashMap<Position, (bool, bool)> = HashMap::new();
+        for &(position, direction) in &trace.steps[1..] {
+            let (vertical, horizontal) = axes.entry(position).or_default();
+            match direction {
+                Direction::UP | Direction::DOWN => *vertical = true,
+                Direction::LEFT | Direction::RIGHT => *horizontal = true,
+            }
+        }
+        let start = trace.steps[0].0;
+
+        let mut rendered = String::new();
+        for (grid::Position { x, y }, &space) in self.lab.cells() {
+            let position = Position { x, y };
+            let space = match axes.get(&position) {
+                _ if position == start => space,
+                Some((true, true)) => '+',
+                Some((true, false)) => '|',
+                Some((false, true)) => '-',
+                _ => space,
+            };
+            rendered.push(space);
+            if x + 1 == self.lab.width() {
+                rendered.push('\n');
+            }
+        }
+
+        rendered
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        lab::{Direction, Position},
+        loops::Outcome,
+        test_support::lab,
+    };
+
+    #[test]
+    fn should_trace_steps_and_turns_in_order() {
+        let lab = lab(&[".#...", "....#", ".^..."]);
+
+        let trace = lab.find_guard().unwrap().trace(&lab);
+
+        assert_eq!(
+            trace.steps,
+            vec![
+                (Position { x: 1, y: 2 }, Direction::UP),
+                (Position { x: 1, y: 1 }, Direction::UP),
+                (Position { x: 1, y: 1 }, Direction::RIGHT),
+                (Position { x: 2, y: 1 }, Direction::RIGHT),
+                (Position { x: 3, y: 1 }, Direction::RIGHT),
+                (Position { x: 3, y: 1 }, Direction::DOWN),
+                (Position { x: 3, y: 2 }, Direction::DOWN),
+            ]
+        );
+        assert_eq!(trace.outcome, Outcome::Exited);
+    }
+
+    #[test]
+    fn should_trace_every_quarter_turn_of_a_u_turn() {
+        let lab = lab(&[".#.", ".^#", "..."]);
+
+        let trace = lab.find_guard().unwrap().trace(&lab);
+
+        assert_eq!(
+            trace.steps,
+            vec![
+                (Position { x: 1, y: 1 }, Direction::UP),
+                (Position { x: 1, y: 1 }, Direction::RIGHT),
+                (Position { x: 1, y: 1 }, Direction::DOWN),
+                (Position { x: 1, y: 2 }, Direction::DOWN),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_render_route_like_the_puzzle() {
+        let lab = lab(&[".#...", "....#", ".^..."]);
+
+        let trace = lab.find_guard().unwrap().trace(&lab);
+
+        assert_eq!(lab.render(&trace), ".#...\n.+-+#\n.^.|.\n");
+    }
+
+    #[test]
+    fn should_render_crossings_and_turn_before_exit() {
+        let crossing = lab(&["..#..", "....#", ".....", "#....", "...#.", "..^.."]);
+        let turn_before_exit = lab(&["..#", "...", "..^"]);
+
+        let trace = crossing.find_guard().unwrap().trace(&crossing);
+        assert_eq!(
+            crossing.render(&trace),
+            ".|#..\n.|++#\n.|||.\n#+++.\n..|#.\n..^..\n"
+        );
+
+        let trace = turn_before_exit
+            .find_guard()
+            .unwrap()
+            .trace(&turn_before_exit);
+        assert_eq!(turn_before_exit.render(&trace), "..#\n..+\n..^\n");
+    }
+}
@@ -0,0 +1,220 @@
+use std::{collections::HashSet, fmt, hash::Hash, marker::PhantomData};
+
+use crate::lab::{Direction, Position};
+
+/// Cells of a lab, one bit each.
+pub type CellSet = BitSet<Position>;
+
+/// Cells of a lab together with the direction the guard faced there, one
+/// bit each.
+pub type StateSet = BitSet<(Position, Direction)>;
+
+/// A set of keys tied to the cells of a `width` by `height` lab, with
+/// `K::LAYERS` bits per cell.
+#[derive(Clone, PartialEq, Eq)]
+pub struct BitSet<K> {
+    width: usize,
+    height: usize,
+    words: Vec<u64>,
+    len: usize,
+    keys: PhantomData<K>,
+}
+
+/// What a `BitSet` can hold: a cell and one of `LAYERS` values on it.
+pub trait Key: Copy {
+    const LAYERS: usize;
+
+    fn split(self) -> (Position, usize);
+
+    fn join(position: Position, layer: usize) -> Self;
+}
+
+impl Key for Position {
+    const LAYERS: usize = 1;
+
+    fn split(self) -> (Position, usize) {
+        (self, 0)
+    }
+
+    fn join(position: Position, _: usize) -> Position {
+        position
+    }
+}
+
+impl Key for (Position, Direction) {
+    const LAYERS: usize = Direction::ALL.len();
+
+    fn split(self) -> (Position, usize) {
+        (self.0, self.1.index())
+    }
+
+    fn join(position: Position, layer: usize) -> (Position, Direction) {
+        (position, Direction::ALL[layer])
+    }
+}
+
+impl<K: Key> BitSet<K> {
+    pub fn new(width: usize, height: usize) -> BitSet<K> {
+        BitSet {
+            width,
+            height,
+            words: vec![0; (width * height * K::LAYERS).div_ceil(64)],
+            len: 0,
+            keys: PhantomData,
+        }
+    }
+
+    /// Returns whether the key was not in the set yet.
+    ///
+    /// Panics if its position is outside of the lab.
+    pub fn insert(&mut self, key: K) -> bool {
+        let Some(index) = self.index(key) else {
+            let (position, _) = key.split();
+            panic!(
+                "({}, {}) is outside of the {}x{} lab",
+                position.x, position.y, self.width, self.height
+            );
+        };
+        let (word, mask) = (index / 64, 1 << (index % 64));
+        let inserted = self.words[word] & mask == 0;
+        self.words[word] |= mask;
+        self.len += usize::from(inserted);
+        inserted
+    }
+
+    /// Returns whether the key was in the set.
+    pub fn remove(&mut self, key: K) -> bool {
+        let Some(index) = self.index(key) else {
+            return false;
+        };
+        let (word, mask) = (index / 64, 1 << (index % 64));
+        let removed = self.words[word] & mask != 0;
+        self.words[word] &= !mask;
+        self.len -= usize::from(removed);
+        removed
+    }
+
+    /// Never true for a position outside of the lab.
+    pub fn contains(&self, key: K) -> bool {
+        self.index(key)
+            .is_some_and(|index| self.words[index / 64] & (1 << (index % 64)) != 0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The keys in the set, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = K> + '_ {
+        let bits = self.words.iter().enumerate().flat_map(|(index, &word)| {
+            (0..64)
+                .filter(move |bit| word & (1 << bit) != 0)
+                .map(move |bit| index * 64 + bit)
+        });
+
+        bits.map(|bit| {
+            let (cell, layer) = (bit / K::LAYERS, bit % K::LAYERS);
+            let position = Position {
+                x: cell % self.width,
+                y: cell / self.width,
+            };
+            K::join(position, layer)
+        })
+    }
+
+    fn index(&self, key: K) -> Option<usize> {
+        let (position, layer) = key.split();
+        (position.x < self.width && position.y < self.height)
+            .then(|| (position.y * self.width + position.x) * K::LAYERS + layer)
+    }
+}
+
+impl<K: Key> Extend<K> for BitSet<K> {
+    fn extend<I: IntoIterator<Item = K>>(&mut self, keys: I) {
+        for key in keys {
+            self.insert(key);
+        }
+    }
+}
+
+impl<K: Key + Eq + Hash> PartialEq<HashSet<K>> for BitSet<K> {
+    fn eq(&self, other: &HashSet<K>) -> bool {
+        self.len() == other.len() && other.iter().all(|&key| self.contains(key))
+    }
+}
+
+impl<K: Key + fmt::Debug> fmt::Debug for BitSet<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use crate::{
+        lab::{Direction, Position},
+        visited::{CellSet, StateSet},
+    };
+
+    #[test]
+    fn should_insert_cells_once() {
+        let mut cells = CellSet::new(70, 2);
+
+        assert!(cells.insert(Position { x: 3, y: 1 }));
+        assert!(cells.insert(Position { x: 69, y: 0 }));
+        assert!(!cells.insert(Position { x: 3, y: 1 }));
+        assert!(cells.insert(Position { x: 0, y: 0 }));
+        assert!(cells.remove(Position { x: 0, y: 0 }));
+        assert!(!cells.remove(Position { x: 0, y: 0 }));
+
+        assert_eq!(cells.len(), 2);
+        assert!(cells.contains(Position { x: 69, y: 0 }));
+        assert!(!cells.contains(Position { x: 68, y: 0 }));
+        assert_eq!(
+            cells.iter().collect::<Vec<_>>(),
+            vec![Position { x: 69, y: 0 }, Position { x: 3, y: 1 }]
+        );
+        assert_eq!(
+            cells,
+            HashSet::from([Position { x: 3, y: 1 }, Position { x: 69, y: 0 }])
+        );
+    }
+
+    #[test]
+    fn should_tell_directions_apart() {
+        let mut states = StateSet::new(3, 3);
+        let position = Position { x: 2, y: 2 };
+
+        assert!(states.insert((position, Direction::UP)));
+        assert!(states.insert((position, Direction::LEFT)));
+        assert!(!states.insert((position, Direction::UP)));
+
+        assert_eq!(states.len(), 2);
+        assert!(!states.contains((position, Direction::DOWN)));
+        assert_eq!(
+            states.iter().collect::<Vec<_>>(),
+            vec![(position, Direction::UP), (position, Direction::LEFT)]
+        );
+    }
+
+    #[test]
+    fn should_not_contain_positions_outside_the_lab() {
+        let cells = CellSet::new(3, 3);
+        let states = StateSet::new(3, 3);
+
+        assert!(!cells.contains(Position { x: 3, y: 0 }));
+        assert!(!states.contains((Position { x: 0, y: 7 }, Direction::UP)));
+    }
+
+    #[test]
+    #[should_panic(expected = "(3, 0) is outside of the 3x3 lab")]
+    fn should_reject_positions_outside_the_lab() {
+        CellSet::new(3, 3).insert(Position { x: 3, y: 0 });
+    }
+}